@@ -11,6 +11,7 @@ use std::path::PathBuf;
 use std::time::SystemTime;
 use text_io;
 use wfm_rs::response::ShortItem;
+use wfm_rs::Platform;
 
 type JwtToken = String;
 
@@ -19,12 +20,17 @@ pub struct Config {
     jwt_token: JwtToken,
     items_timestamp: u64,
     username: String,
+    // configs written before the platform was stored are all pc
+    #[serde(default)]
+    platform: Platform,
     pub items: Vec<wfm_rs::response::ShortItem>,
 }
 
 impl Config {
     pub fn user(&self) -> wfm_rs::User {
-        wfm_rs::User::_from_jwt_token(&self.jwt_token, &self.username)
+        wfm_rs::Client::builder()
+            .platform(self.platform.clone())
+            .user_from_jwt_token(&self.jwt_token, &self.username)
     }
 }
 
@@ -42,9 +48,7 @@ pub async fn run() -> Result<Config> {
 
             if (unix_timestamp()? - cfg.items_timestamp) > ITEMS_CACHE_EXPIRY_S {
                 print!("Refreshing items...   ");
-                let mut items = wfm_rs::User::_from_jwt_token(&cfg.jwt_token, "")
-                    .get_items()
-                    .await?;
+                let mut items = wfm_rs::Client::new().get_items().await?;
                 fix_items(&mut items);
                 cfg.items = items;
                 cfg.items_timestamp = SystemTime::now()
@@ -60,16 +64,15 @@ pub async fn run() -> Result<Config> {
             fs::create_dir(&data_path_screenshot);
             File::create(&data_path_config);
 
-            let (token, username) = login_process().await?;
+            let (token, username, platform) = login_process().await?;
 
             print!("Building config...   ");
             let cfg = Config {
-                items: wfm_rs::User::_from_jwt_token(&token, "")
-                    .get_items()
-                    .await?,
+                items: wfm_rs::Client::new().get_items().await?,
                 items_timestamp: unix_timestamp()?,
                 jwt_token: token,
                 username,
+                platform,
             };
             println!("success!");
 
@@ -83,14 +86,19 @@ pub async fn run() -> Result<Config> {
 }
 
 // guide user through login process
-async fn login_process() -> Result<(JwtToken, String)> {
+async fn login_process() -> Result<(JwtToken, String, Platform)> {
     println!("You need to log in with your warframe.market account!");
     println!("This program does not store your e-mail and/or password, they are both only used once, to log into the warframe.market API.");
     println!("The only thing related to your account this program stores is the token received from the API.");
 
     let email = prompt("E-mail:");
     let password = prompt("Password:");
-    let platform = prompt("Platform (pc, xbox or ps4):");
+    let platform = loop {
+        match prompt("Platform (pc, xbox, ps4 or switch):").parse::<Platform>() {
+            Ok(x) => break x,
+            Err(e) => println!("{}, try again", e),
+        }
+    };
 
     println!("\n");
 
@@ -98,7 +106,7 @@ async fn login_process() -> Result<(JwtToken, String)> {
     let user = wfm_rs::User::login(&email, &password, &platform, "en").await?;
     println!("success!");
 
-    Ok((user._jwt_token(), user.username()))
+    Ok((user._jwt_token(), user.username(), platform))
 }
 
 fn prompt(text: &str) -> String {
@@ -3,12 +3,15 @@ use colored::*;
 use device_query::{DeviceQuery, DeviceState, Keycode};
 use ocr::OCREngine;
 use screenshot_rs;
-use std::{thread, time::Duration};
+use std::cmp::Ordering;
+use std::time::Duration;
 use tokio;
 use util::{clear_terminal, screenshot_path, unix_timestamp};
 use wfm_rs::model::PostOrderDescriptor;
 use wfm_rs::response::ShortItem;
-use wfm_rs::shared::OrderType;
+use wfm_rs::shared::{OrderType, UserStatus};
+use wfm_rs::statistics::Variant;
+use wfm_rs::ws::Connection;
 use wfm_rs::User;
 
 mod config;
@@ -51,62 +54,305 @@ async fn main() {
     let config = config::run().await.unwrap();
     let user = config.user();
 
-    let device = DeviceState::new();
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(|x| x.as_str()) {
+        Some("summary") => {
+            if let Err(e) = print_weekly_summary(&user).await {
+                eprintln!("Failed to get your statistics: {}", e);
+            }
+            return;
+        }
+        Some("sold") => {
+            let item = match args.get(2) {
+                Some(x) => x,
+                None => {
+                    eprintln!("Usage: wfm_cli sold <item> [quantity]");
+                    return;
+                }
+            };
+            let quantity = match args.get(3).map(|x| x.parse::<u16>()) {
+                Some(Ok(0)) | Some(Err(_)) => {
+                    eprintln!("The quantity has to be a number above 0");
+                    return;
+                }
+                Some(Ok(x)) => x,
+                None => 1,
+            };
+            if let Err(e) = record_sale(&user, &config.items, item, quantity).await {
+                eprintln!("Failed to record the sale: {}", e);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let ingame = if std::env::args().any(|x| x == "--ingame") {
+        match show_ingame(&user).await {
+            Ok(connection) => Some(connection),
+            Err(e) => {
+                eprintln!("Failed to set your status to ingame: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     let engine = OCREngine::new(config.items);
     println!("You may now press '~' whenever you get to the relic reward screen");
 
+    let result = tokio::select! {
+        result = scan_rewards(&engine, &user) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    };
+
+    if let Some(connection) = ingame {
+        go_invisible(connection).await;
+    }
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// waits for '~' and prints the prices of the rewards on screen, until something fails
+async fn scan_rewards(engine: &OCREngine, user: &User) -> Result<()> {
+    let device = DeviceState::new();
+
     loop {
         let keys: Vec<Keycode> = device.get_keys();
         if keys.contains(&Keycode::Grave) {
             println!("Scanning...");
-            let mut screenshot_path = screenshot_path().unwrap();
-            screenshot_path.push(format!("{}.png", unix_timestamp().unwrap()));
+            let mut screenshot_path = screenshot_path()?;
+            screenshot_path.push(format!("{}.png", unix_timestamp()?));
             let screenshot_path_str = screenshot_path.to_string_lossy().to_string();
             screenshot_rs::screenshot_window(screenshot_path_str.clone());
-            let items = engine.ocr(&screenshot_path_str).unwrap();
+            let items = engine.ocr(&screenshot_path_str)?;
             //fs::remove_file(screenshot_path).unwrap();
 
             let mut all_item_stats = Vec::new();
 
             for item in items {
-                all_item_stats.push(get_item_info(&item, &user).await.unwrap());
+                match get_item_info(&item, user).await {
+                    Ok(stats) => all_item_stats.push(stats),
+                    Err(e) => eprintln!("Failed to get prices for {}: {}", item.item_name, e),
+                }
             }
 
-            all_item_stats.sort_by(|a, b| a.avg_price.partial_cmp(&b.avg_price).unwrap());
+            all_item_stats.sort_by(|a, b| {
+                a.avg_price
+                    .partial_cmp(&b.avg_price)
+                    .unwrap_or(Ordering::Equal)
+            });
             let all_item_stats: Vec<&ItemStats> = all_item_stats.iter().rev().collect();
 
             clear_terminal();
 
             for (idx, item) in all_item_stats.iter().enumerate() {
-                let msg = format!(
-                    "{} | {:.1} platinum average | {:.0} sold in the last 48 hours",
-                    item.item.item_name, item.avg_price, item.volume
+                let best_ingame = match item.best_ingame_price {
+                    Some(price) => format!("{:.0} platinum", price),
+                    None => "none".to_string(),
+                };
+                let mut msg = format!(
+                    "{} | {:.1} platinum average | {:.0} sold in the last 48 hours | cheapest in-game seller: {}",
+                    item.item.item_name, item.avg_price, item.volume, best_ingame
                 );
+                if item.variants.len() > 1 {
+                    let prices: Vec<String> = item
+                        .variants
+                        .iter()
+                        .map(|x| format!("{}: {:.1}", x.variant, x.avg_price))
+                        .collect();
+                    msg.push_str(&format!(" | {}", prices.join(", ")));
+                }
                 println!("{}", msg.color(RESULT_COLORS[idx]));
             }
         }
-        thread::sleep(Duration::from_millis(10));
+        // sleeping on the runtime lets ctrl-c interrupt the loop
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// shows the user as ingame while the cli runs, `go_invisible` undoes it
+async fn show_ingame(user: &User) -> Result<Connection> {
+    let connection = Connection::connect(user).await?;
+    connection.set_status(&UserStatus::Ingame)?;
+    println!("Your status is now ingame, press ctrl-c to quit and go invisible");
+
+    Ok(connection)
+}
+
+/// sets the status back to invisible, giving up after a few seconds so quitting never hangs
+async fn go_invisible(connection: Connection) {
+    let reset = async move {
+        connection.set_status(&UserStatus::Invisible)?;
+        connection.shutdown().await;
+        Ok::<(), wfm_rs::Error>(())
+    };
+
+    match tokio::time::timeout(Duration::from_secs(5), reset).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Failed to set your status back to invisible: {}", e),
+        Err(_) => eprintln!("Timed out setting your status back to invisible"),
+    }
+}
+
+/// platinum earned and spent on closed orders in the last 7 days
+async fn print_weekly_summary(user: &User) -> Result<()> {
+    let statistics = user.get_profile_statistics(&user.username()).await?;
+    let week_ago = chrono::Utc::now() - chrono::Duration::days(7);
+    let days: Vec<_> = statistics
+        .closed_orders
+        ._90_days
+        .iter()
+        .filter(|x| matches!(x.datetime_utc(), Some(day) if day >= week_ago))
+        .collect();
+
+    let sold: u32 = days.iter().map(|x| x.sell_volume).sum();
+    let earned: f64 = days.iter().map(|x| x.sell_platinum).sum();
+    let bought: u32 = days.iter().map(|x| x.buy_volume).sum();
+    let spent: f64 = days.iter().map(|x| x.buy_platinum).sum();
+
+    println!("Last 7 days:");
+    println!("  sold {} items for {:.0} platinum", sold, earned);
+    println!("  bought {} items for {:.0} platinum", bought, spent);
+    let net = format!("  net {:+.0} platinum", earned - spent);
+    if earned >= spent {
+        println!("{}", net.green());
+    } else {
+        println!("{}", net.red());
+    }
+
+    Ok(())
+}
+
+/// closes `quantity` of your sell order for the item, `item` is its url name or in-game name
+async fn record_sale(user: &User, items: &[ShortItem], item: &str, quantity: u16) -> Result<()> {
+    let url_name = items
+        .iter()
+        .find(|x| x.url_name == item || x.item_name.eq_ignore_ascii_case(item))
+        .map(|x| x.url_name.as_str())
+        .unwrap_or(item);
+
+    let orders = user.get_user_orders().await?;
+    let matching: Vec<_> = orders
+        .sell_orders
+        .iter()
+        .filter(|x| x.item.url_name == url_name)
+        .collect();
+    let order = match matching.as_slice() {
+        [] => anyhow::bail!("you have no sell order for {}", url_name),
+        [x] => x,
+        _ => {
+            // different ranks or subtypes of the same item, closing the wrong one would be worse
+            let listed: Vec<String> = matching
+                .iter()
+                .map(|x| {
+                    let variant = Variant {
+                        mod_rank: x.mod_rank,
+                        subtype: x.subtype.clone(),
+                    };
+                    format!("{} ({} at {:.0} platinum)", variant, x.quantity, x.platinum)
+                })
+                .collect();
+            anyhow::bail!(
+                "you have {} sell orders for {}, close the right one on the site: {}",
+                matching.len(),
+                url_name,
+                listed.join(", ")
+            );
+        }
+    };
+
+    let mut remaining = order.quantity;
+    for sold in 1..=quantity {
+        match user.close_order(*order).await {
+            Ok(Some(updated)) => remaining = updated.quantity,
+            Ok(None) => {
+                println!("Sold {} of {}, the order is gone now", sold, url_name);
+                return Ok(());
+            }
+            Err(e) => anyhow::bail!(
+                "{} of {} were closed before this failed, don't count them again: {}",
+                sold - 1,
+                url_name,
+                e
+            ),
+        }
     }
+    println!(
+        "Sold {} of {}, {} left on the order",
+        quantity, url_name, remaining
+    );
+
+    Ok(())
 }
 
 #[derive(Clone)]
 struct ItemStats {
+    /// volume and average price of the unranked or intact variant
     volume: f32,
     avg_price: f32,
+    best_ingame_price: Option<f64>,
+    variants: Vec<VariantStats>,
     item: ShortItem,
 }
 
+/// prices for a single mod rank or subtype, these are never mixed
+#[derive(Clone)]
+struct VariantStats {
+    variant: Variant,
+    volume: f32,
+    avg_price: f32,
+}
+
 async fn get_item_info(item: &ShortItem, user: &User) -> Result<ItemStats> {
     let statistics = user.get_item_market_statistics(item).await?;
 
-    let last_stats = &statistics.statistics_closed._48_hours;
-    let avg_price: f32 =
-        last_stats.iter().map(|x| x.avg_price).sum::<f32>() / last_stats.len() as f32;
-    let volume: f32 = last_stats.iter().map(|x| x.volume).sum();
+    let variants: Vec<VariantStats> = statistics
+        .statistics_closed
+        .by_variant_48_hours()
+        .into_iter()
+        .map(|(variant, rows)| VariantStats {
+            variant,
+            volume: rows.iter().map(|x| x.volume).sum(),
+            avg_price: rows.iter().map(|x| x.avg_price).sum::<f32>() / rows.len() as f32,
+        })
+        .collect();
+
+    // the variant a fresh drop is: unranked, and intact for items with subtypes
+    let primary = variants
+        .iter()
+        .find(|x| {
+            matches!(x.variant.mod_rank, None | Some(0))
+                && matches!(x.variant.subtype.as_deref(), None | Some("intact"))
+        })
+        .or_else(|| variants.first());
+    let (avg_price, volume) = match primary {
+        Some(x) => (x.avg_price, x.volume),
+        None => (0.0, 0.0),
+    };
+    let primary = primary.map(|x| x.variant.clone()).unwrap_or_default();
+
+    // only used for one column, so a failure here shouldn't hide the statistics
+    let best_ingame_price = user.get_order_book(item).await.ok().and_then(|book| {
+        // compare with sellers of the same variant as the average next to it
+        let mut book = book.ingame();
+        if let Some(rank) = primary.mod_rank {
+            book = book.rank(rank);
+        }
+        if let Some(subtype) = &primary.subtype {
+            book = book.subtype(subtype);
+        }
+        book.best_sell(true).map(|x| x.platinum)
+    });
 
     Ok(ItemStats {
         volume,
         avg_price,
+        best_ingame_price,
+        variants,
         item: item.clone(),
     })
 }
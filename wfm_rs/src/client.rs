@@ -0,0 +1,378 @@
+use crate::search::{LichSearch, RivenSearch};
+use crate::{
+    get_endpoint, get_endpoint_with_query, response, traits, OrderBook, Platform, RateLimiter,
+    Result, RetryPolicy, RivenCatalog, User, BASE_URL,
+};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+
+pub(crate) const WS_URL: &str = "wss://warframe.market/socket";
+
+/// everything needed to send a request, cheap to clone
+#[derive(Clone, Debug)]
+pub(crate) struct Transport {
+    pub client: reqwest::Client,
+    pub base_url: String,
+    pub default_headers: HeaderMap,
+    pub limiter: RateLimiter,
+    pub retry: RetryPolicy,
+    pub platform: Platform,
+    pub language: String,
+    #[cfg_attr(not(feature = "ws"), allow(dead_code))]
+    pub ws_url: String,
+}
+
+impl Transport {
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// the default headers, with the authorization, content type, platform and language on top
+    pub fn headers(&self, jwt: Option<&str>) -> Result<HeaderMap> {
+        let mut headers = self.default_headers.clone();
+        if let Some(jwt) = jwt {
+            headers.insert("authorization", jwt.parse()?);
+        }
+        headers.insert("Content-Type", "application/json".parse()?);
+        headers.insert("platform", self.platform.as_str().parse()?);
+        headers.insert("language", self.language.parse()?);
+
+        Ok(headers)
+    }
+}
+
+/// a client without an account, for the public read-only endpoints
+#[derive(Clone, Debug)]
+pub struct Client {
+    pub(crate) transport: Transport,
+}
+
+impl Client {
+    pub fn new() -> Client {
+        ClientBuilder::new().build()
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        platform: &Platform,
+        language: &str,
+    ) -> Result<User> {
+        User::login_with(self.clone(), email, password, platform, language).await
+    }
+
+    pub fn user_from_jwt_token(&self, jwt_token: &str, username: &str) -> User {
+        User::from_client(self.clone(), jwt_token, username)
+    }
+
+    pub fn platform(&self) -> &Platform {
+        &self.transport.platform
+    }
+
+    pub fn language(&self) -> &str {
+        &self.transport.language
+    }
+
+    /// a copy of this client for another platform, use this to override the platform for a single call
+    pub fn with_platform(&self, platform: Platform) -> Client {
+        let mut client = self.clone();
+        client.transport.platform = platform;
+        client
+    }
+
+    /// a copy of this client for another language, for example `de` or `zh-hans`
+    pub fn with_language(&self, language: &str) -> Client {
+        let mut client = self.clone();
+        client.transport.language = language.to_string();
+        client
+    }
+
+    pub async fn get_items(&self) -> Result<Vec<response::ShortItem>> {
+        Ok(
+            get_endpoint::<response::Items>(&self.transport, "/items", None)
+                .await?
+                .items,
+        )
+    }
+
+    pub async fn get_item<T: traits::ItemUrl>(&self, item: &T) -> Result<response::LongItem> {
+        Ok(get_endpoint::<response::LongItemWrapper>(
+            &self.transport,
+            &format!("/items/{}", item.item_url()),
+            None,
+        )
+        .await?
+        .item)
+    }
+
+    pub async fn get_item_orders<T: traits::ItemUrl>(
+        &self,
+        item: &T,
+    ) -> Result<Vec<response::Order>> {
+        Ok(get_endpoint::<response::Orders>(
+            &self.transport,
+            &format!("/items/{}/orders", item.item_url()),
+            None,
+        )
+        .await?
+        .orders)
+    }
+
+    pub async fn get_order_book<T: traits::ItemUrl>(&self, item: &T) -> Result<OrderBook> {
+        Ok(OrderBook::new(self.get_item_orders(item).await?))
+    }
+
+    pub async fn get_item_market_statistics<T: traits::ItemUrl>(
+        &self,
+        item: &T,
+    ) -> Result<response::MarketStatisticsWrapper> {
+        get_endpoint(
+            &self.transport,
+            &format!("/items/{}/statistics", item.item_url()),
+            None,
+        )
+        .await
+    }
+
+    pub async fn get_profile(&self, ingame_name: &str) -> Result<response::Profile> {
+        Ok(get_endpoint::<response::ProfileWrapper>(
+            &self.transport,
+            &format!("/profile/{}", ingame_name),
+            None,
+        )
+        .await?
+        .profile)
+    }
+
+    pub async fn get_profile_statistics(
+        &self,
+        ingame_name: &str,
+    ) -> Result<response::ProfileStatistics> {
+        Ok(get_endpoint::<response::ProfileStatisticsWrapper>(
+            &self.transport,
+            &format!("/profile/{}/statistics", ingame_name),
+            None,
+        )
+        .await?
+        .statistics)
+    }
+
+    pub async fn get_reviews(&self, ingame_name: &str) -> Result<Vec<response::Review>> {
+        Ok(get_endpoint::<response::Reviews>(
+            &self.transport,
+            &format!("/profile/{}/reviews", ingame_name),
+            None,
+        )
+        .await?
+        .reviews)
+    }
+
+    pub async fn get_riven_items(&self) -> Result<Vec<response::RivenItem>> {
+        Ok(
+            get_endpoint::<response::RivenItems>(&self.transport, "/riven/items", None)
+                .await?
+                .items,
+        )
+    }
+
+    pub async fn get_riven_attributes(&self) -> Result<Vec<response::RivenAttributeDefinition>> {
+        Ok(
+            get_endpoint::<response::RivenAttributes>(&self.transport, "/riven/attributes", None)
+                .await?
+                .attributes,
+        )
+    }
+
+    /// fetches the riven items and attributes into a lookup table
+    pub async fn get_riven_catalog(&self) -> Result<RivenCatalog> {
+        Ok(RivenCatalog::new(
+            self.get_riven_items().await?,
+            self.get_riven_attributes().await?,
+        ))
+    }
+
+    pub async fn get_lich_weapons(&self) -> Result<Vec<response::LichWeapon>> {
+        self.get_weapons("/lich/weapons").await
+    }
+
+    pub async fn get_lich_ephemeras(&self) -> Result<Vec<response::Ephemera>> {
+        self.get_ephemeras("/lich/ephemeras").await
+    }
+
+    pub async fn get_sister_weapons(&self) -> Result<Vec<response::LichWeapon>> {
+        self.get_weapons("/sister/weapons").await
+    }
+
+    pub async fn get_sister_ephemeras(&self) -> Result<Vec<response::Ephemera>> {
+        self.get_ephemeras("/sister/ephemeras").await
+    }
+
+    async fn get_weapons(&self, url: &str) -> Result<Vec<response::LichWeapon>> {
+        Ok(
+            get_endpoint::<response::LichWeapons>(&self.transport, url, None)
+                .await?
+                .weapons,
+        )
+    }
+
+    async fn get_ephemeras(&self, url: &str) -> Result<Vec<response::Ephemera>> {
+        Ok(
+            get_endpoint::<response::Ephemeras>(&self.transport, url, None)
+                .await?
+                .ephemeras,
+        )
+    }
+
+    pub async fn search_riven_auctions(
+        &self,
+        search: &RivenSearch,
+    ) -> Result<Vec<response::ProfileAuction>> {
+        self.search_auctions(search, search.platform_override())
+            .await
+    }
+
+    /// kuva lich auctions, or sister of parvos auctions if the search was made with `sister()`
+    pub async fn search_lich_auctions(
+        &self,
+        search: &LichSearch,
+    ) -> Result<Vec<response::ProfileAuction>> {
+        self.search_auctions(search, search.platform_override())
+            .await
+    }
+
+    async fn search_auctions<Q: Serialize>(
+        &self,
+        query: &Q,
+        platform: Option<&Platform>,
+    ) -> Result<Vec<response::ProfileAuction>> {
+        let client = match platform {
+            Some(platform) => self.with_platform(platform.clone()),
+            None => self.clone(),
+        };
+
+        Ok(get_endpoint_with_query::<response::ProfileAuctions, Q>(
+            &client.transport,
+            "/auctions/search",
+            None,
+            query,
+        )
+        .await?
+        .auctions)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Client {
+        Client::new()
+    }
+}
+
+/// configures how requests are sent, for example to use a proxy or point at a mock server
+#[derive(Clone, Debug, Default)]
+pub struct ClientBuilder {
+    base_url: Option<String>,
+    http_client: Option<reqwest::Client>,
+    default_headers: HeaderMap,
+    limiter: Option<RateLimiter>,
+    retry: Option<RetryPolicy>,
+    platform: Option<Platform>,
+    language: Option<String>,
+    ws_url: Option<String>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// defaults to `https://api.warframe.market/v1`, a trailing slash is ignored
+    pub fn base_url(mut self, base_url: &str) -> ClientBuilder {
+        self.base_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// use a pre-built client, for proxies, timeouts or a custom user agent
+    pub fn http_client(mut self, client: reqwest::Client) -> ClientBuilder {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// sent with every request, `authorization` and `Content-Type` are always overwritten
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> ClientBuilder {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HeaderMap) -> ClientBuilder {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> ClientBuilder {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> ClientBuilder {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// defaults to `wss://warframe.market/socket`, only used with the `ws` feature
+    pub fn ws_url(mut self, ws_url: &str) -> ClientBuilder {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// defaults to pc, logging in overrides this with the platform passed to `login`
+    pub fn platform(mut self, platform: Platform) -> ClientBuilder {
+        self.platform = Some(platform);
+        self
+    }
+
+    /// defaults to `en`, logging in overrides this with the language passed to `login`
+    pub fn language(mut self, language: &str) -> ClientBuilder {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub async fn login(
+        self,
+        email: &str,
+        password: &str,
+        platform: &Platform,
+        language: &str,
+    ) -> Result<User> {
+        self.build()
+            .login(email, password, platform, language)
+            .await
+    }
+
+    pub fn user_from_jwt_token(self, jwt_token: &str, username: &str) -> User {
+        self.build().user_from_jwt_token(jwt_token, username)
+    }
+
+    pub fn build(self) -> Client {
+        Client {
+            transport: self.build_transport(),
+        }
+    }
+
+    fn build_transport(self) -> Transport {
+        Transport {
+            client: self.http_client.unwrap_or_default(),
+            base_url: self.base_url.unwrap_or_else(|| BASE_URL.to_string()),
+            default_headers: self.default_headers,
+            limiter: self.limiter.unwrap_or_default(),
+            retry: self.retry.unwrap_or_default(),
+            platform: self.platform.unwrap_or_default(),
+            language: self.language.unwrap_or_else(|| "en".to_string()),
+            ws_url: self.ws_url.unwrap_or_else(|| WS_URL.to_string()),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// 401 or 403, usually means the JWT token is missing or expired
+    #[error("unauthorized ({status}), the jwt token is missing or expired")]
+    Unauthorized {
+        status: StatusCode,
+        error: Option<ApiError>,
+    },
+    /// 404, usually means a bad `url_name` or id
+    #[error("not found")]
+    NotFound { error: Option<ApiError> },
+    /// 429, too many requests were sent to the API
+    #[error("rate limited by the API")]
+    RateLimited { retry_after: Option<Duration> },
+    /// any other 4xx status without a readable error body
+    #[error("client error ({status})")]
+    ClientError { status: StatusCode },
+    /// any 5xx status
+    #[error("server error ({status})")]
+    ServerError {
+        status: StatusCode,
+        error: Option<ApiError>,
+    },
+    /// any other 4xx status with an `{"error": ...}` body
+    #[error("API error ({status}): {error}")]
+    Api { status: StatusCode, error: ApiError },
+    /// the bid was lower than the top bid or the starting price
+    #[error("bid too low ({status}): {error}")]
+    BidTooLow { status: StatusCode, error: ApiError },
+    /// the auction was closed or sold before the bid arrived
+    #[error("auction is closed ({status}): {error}")]
+    AuctionClosed { status: StatusCode, error: ApiError },
+    /// the response body could not be deserialized, `body` holds the raw response
+    #[error("failed to deserialize response: {source}")]
+    Deserialize {
+        source: serde_json::Error,
+        body: String,
+    },
+    #[error("failed to serialize request body: {0}")]
+    Serialize(serde_json::Error),
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+    #[error("invalid header value: {0}")]
+    InvalidHeader(#[from] reqwest::header::InvalidHeaderValue),
+    #[error("no jwt token in the signin response headers")]
+    MissingToken,
+    #[cfg(feature = "ws")]
+    #[error("websocket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+    /// the websocket connection was closed and won't reconnect
+    #[cfg(feature = "ws")]
+    #[error("websocket connection is closed")]
+    WebSocketClosed,
+    /// a message couldn't be written to the websocket, or the site never confirmed it
+    #[cfg(feature = "ws")]
+    #[error("the message was not sent")]
+    NotSent,
+}
+
+impl Error {
+    /// builds the error for a non-success status, the API error body is kept if there is one
+    pub(crate) fn from_status(
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        body: &str,
+    ) -> Error {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Error::RateLimited { retry_after };
+        }
+
+        let error = serde_json::from_str::<ApiErrorWrapper>(body)
+            .ok()
+            .map(|x| x.error);
+
+        match (status, error) {
+            (StatusCode::UNAUTHORIZED, error) | (StatusCode::FORBIDDEN, error) => {
+                Error::Unauthorized { status, error }
+            }
+            (StatusCode::NOT_FOUND, error) => Error::NotFound { error },
+            (s, error) if s.is_server_error() => Error::ServerError { status, error },
+            (_, Some(error)) => Error::Api { status, error },
+            (_, None) => Error::ClientError { status },
+        }
+    }
+
+    /// the HTTP status of the response, if the error came from one
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Unauthorized { status, .. }
+            | Error::ClientError { status }
+            | Error::ServerError { status, .. }
+            | Error::Api { status, .. }
+            | Error::BidTooLow { status, .. }
+            | Error::AuctionClosed { status, .. } => Some(*status),
+            Error::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+            Error::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Error::Transport(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api { error, .. }
+            | Error::BidTooLow { error, .. }
+            | Error::AuctionClosed { error, .. } => Some(error),
+            Error::Unauthorized { error, .. }
+            | Error::NotFound { error }
+            | Error::ServerError { error, .. } => error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// turns the API errors for a rejected bid into `BidTooLow` and `AuctionClosed`,
+    /// only meant for errors returned by the bid endpoint
+    pub(crate) fn into_bid_error(self) -> Error {
+        match self {
+            Error::Api { status, error } => {
+                let messages = error.messages();
+                if messages.contains(&"app.bid.too_low") {
+                    Error::BidTooLow { status, error }
+                } else if messages.contains(&"app.auction.closed") {
+                    Error::AuctionClosed { status, error }
+                } else {
+                    Error::Api { status, error }
+                }
+            }
+            e => e,
+        }
+    }
+
+    pub(crate) fn deserialize(source: serde_json::Error, body: &str) -> Error {
+        Error::Deserialize {
+            source,
+            body: body.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct ApiErrorWrapper {
+    pub error: ApiError,
+}
+
+/// the `error` object warframe.market returns
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ApiError {
+    Message(String),
+    Fields(HashMap<String, Vec<String>>),
+}
+
+impl ApiError {
+    /// the validation messages for a single field, for example `item_id` when posting an order
+    pub fn field(&self, name: &str) -> Option<&[String]> {
+        match self {
+            ApiError::Fields(fields) => fields.get(name).map(|x| x.as_slice()),
+            ApiError::Message(_) => None,
+        }
+    }
+
+    /// every message in the error, regardless of the field it belongs to
+    pub fn messages(&self) -> Vec<&str> {
+        match self {
+            ApiError::Message(msg) => vec![msg.as_str()],
+            ApiError::Fields(fields) => fields.values().flatten().map(|x| x.as_str()).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Message(msg) => write!(f, "{}", msg),
+            ApiError::Fields(fields) => {
+                let mut names: Vec<&String> = fields.keys().collect();
+                names.sort();
+                let parts: Vec<String> = names
+                    .into_iter()
+                    .map(|name| format!("{}: {}", name, fields[name].join(", ")))
+                    .collect();
+                write!(f, "{}", parts.join("; "))
+            }
+        }
+    }
+}
@@ -1,104 +1,192 @@
 #![allow(unused_assignments)]
-use anyhow::Result;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::time::Duration;
 
+pub mod client;
+pub mod error;
 pub mod model;
+pub mod orderbook;
+pub mod ratelimit;
 pub mod request;
 pub mod response;
+pub mod retry;
+pub mod riven;
+pub mod search;
 pub mod shared;
+pub mod statistics;
 pub mod traits;
+#[cfg(feature = "ws")]
+pub mod ws;
 
+pub(crate) use client::Transport;
+pub use client::{Client, ClientBuilder};
+pub use error::{ApiError, Error, Result};
 pub use model::User;
+pub use orderbook::OrderBook;
+pub use ratelimit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use riven::RivenCatalog;
 pub use shared::Platform;
 
 pub(crate) const BASE_URL: &str = "https://api.warframe.market/v1";
 
 pub(crate) async fn get_endpoint<T: DeserializeOwned>(
-    client: &reqwest::Client,
+    transport: &Transport,
     url: &str,
-    jwt: &str,
+    jwt: Option<&str>,
 ) -> Result<T> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("authorization", jwt.parse()?);
-    headers.insert("Content-Type", "application/json".parse()?);
+    let request = transport
+        .client
+        .get(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .build()?;
 
-    let raw = client
-        .get(format!("{}{}", BASE_URL, url))
-        .headers(headers)
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    let base: response::ResponseWrapper<T> = serde_json::from_str(&raw)?;
+    parse_payload(&send(transport, request).await?)
+}
 
-    Ok(base.payload)
+/// like `get_endpoint`, with `query` serialized into the query string
+pub(crate) async fn get_endpoint_with_query<T: DeserializeOwned, Q: Serialize>(
+    transport: &Transport,
+    url: &str,
+    jwt: Option<&str>,
+    query: &Q,
+) -> Result<T> {
+    let request = transport
+        .client
+        .get(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .query(query)
+        .build()?;
+
+    parse_payload(&send(transport, request).await?)
 }
 
 pub(crate) async fn post_endpoint<T: DeserializeOwned, B: Serialize>(
-    client: &reqwest::Client,
+    transport: &Transport,
     url: &str,
-    jwt: &str,
+    jwt: Option<&str>,
     body: &B,
 ) -> Result<T> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("authorization", jwt.parse()?);
-    headers.insert("Content-Type", "application/json".parse()?);
-
-    let raw = client
-        .post(format!("{}{}", BASE_URL, url))
-        .headers(headers)
-        .body(serde_json::to_string(body)?)
-        .send()
-        .await?
-        .text()
-        .await?;
-
-    let base: response::ResponseWrapper<T> = serde_json::from_str(&raw)?;
-
-    Ok(base.payload)
+    let request = transport
+        .client
+        .post(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .body(serde_json::to_string(body).map_err(Error::Serialize)?)
+        .build()?;
+
+    parse_payload(&send(transport, request).await?)
 }
 
 pub(crate) async fn delete_endpoint<T: DeserializeOwned>(
-    client: &reqwest::Client,
+    transport: &Transport,
     url: &str,
-    jwt: &str,
+    jwt: Option<&str>,
 ) -> Result<T> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("authorization", jwt.parse()?);
-    headers.insert("Content-Type", "application/json".parse()?);
-
-    let raw = client
-        .delete(format!("{}{}", BASE_URL, url))
-        .headers(headers)
-        .send()
-        .await?
-        .text()
-        .await?;
+    let request = transport
+        .client
+        .delete(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .build()?;
 
-    let base: response::ResponseWrapper<T> = serde_json::from_str(&raw)?;
-
-    Ok(base.payload)
+    parse_payload(&send(transport, request).await?)
 }
 
 pub(crate) async fn put_endpoint<T: Serialize>(
-    client: &reqwest::Client,
+    transport: &Transport,
     url: &str,
-    jwt: &str,
+    jwt: Option<&str>,
     body: &T,
 ) -> Result<()> {
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("authorization", jwt.parse()?);
-    headers.insert("Content-Type", "application/json".parse()?);
-
-    let raw = client
-        .put(format!("{}{}", BASE_URL, url))
-        .headers(headers)
-        .body(serde_json::to_string(body)?)
-        .send()
-        .await?
-        .error_for_status()?;
+    let request = transport
+        .client
+        .put(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .body(serde_json::to_string(body).map_err(Error::Serialize)?)
+        .build()?;
+
+    send(transport, request).await?;
 
     Ok(())
 }
+
+/// like `put_endpoint`, for the few PUT endpoints whose response is needed
+pub(crate) async fn put_endpoint_with_response<T: DeserializeOwned, B: Serialize>(
+    transport: &Transport,
+    url: &str,
+    jwt: Option<&str>,
+    body: &B,
+) -> Result<T> {
+    let request = transport
+        .client
+        .put(transport.url(url))
+        .headers(transport.headers(jwt)?)
+        .body(serde_json::to_string(body).map_err(Error::Serialize)?)
+        .build()?;
+
+    parse_payload(&send(transport, request).await?)
+}
+
+/// sends a request through the rate limiter, retrying transient failures according to `retry`
+pub(crate) async fn send(transport: &Transport, request: reqwest::Request) -> Result<String> {
+    let retry = &transport.retry;
+    let mut attempt = 1;
+
+    loop {
+        // bodies are always strings, so cloning can't fail
+        let attempt_request = request.try_clone().expect("request body is not a stream");
+        transport.limiter.acquire().await;
+
+        let can_retry = retry.allows(request.method(), attempt);
+        match transport.client.execute(attempt_request).await {
+            Ok(response) if can_retry && RetryPolicy::is_retry_status(response.status()) => {
+                let wait = match retry_after(response.headers()) {
+                    // longer than we'd ever back off, let the caller decide what to do
+                    Some(x) if x > retry.max_delay => return read_response(response).await,
+                    Some(x) => x,
+                    None => retry.backoff(attempt),
+                };
+                tokio::time::sleep(wait).await;
+            }
+            Ok(response) => return read_response(response).await,
+            Err(e) if can_retry && RetryPolicy::is_transient(&e) => {
+                tokio::time::sleep(retry.backoff(attempt)).await;
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        attempt += 1;
+    }
+}
+
+/// reads the body of a response, turning any non-success status into an error
+pub(crate) async fn read_response(response: reqwest::Response) -> Result<String> {
+    let status = response.status();
+    let retry_after = retry_after(response.headers());
+    let raw = response.text().await?;
+
+    if !status.is_success() {
+        return Err(Error::from_status(status, retry_after, &raw));
+    }
+
+    Ok(raw)
+}
+
+pub(crate) fn parse_payload<T: DeserializeOwned>(raw: &str) -> Result<T> {
+    let base: response::ResponseWrapper<T> =
+        serde_json::from_str(raw).map_err(|e| Error::deserialize(e, raw))?;
+
+    Ok(base.payload)
+}
+
+/// only the delay-seconds form of `Retry-After` is supported, the API doesn't send dates
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
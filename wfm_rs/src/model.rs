@@ -1,17 +1,17 @@
 use crate::response::ProfileOrderResponseWrapper;
-use crate::shared::OrderType;
-use crate::traits::OrderID;
+use crate::search::{LichSearch, RivenSearch};
+use crate::shared::{OrderType, ReviewType};
+use crate::traits::{AuctionID, ChatID, OrderID};
 use crate::{
-    delete_endpoint, get_endpoint, post_endpoint, put_endpoint, request, response, traits,
-    Platform, BASE_URL,
+    delete_endpoint, get_endpoint, parse_payload, post_endpoint, put_endpoint,
+    put_endpoint_with_response, read_response, request, response, traits, Client, Error, OrderBook,
+    Platform, RateLimiter, Result, RetryPolicy, RivenCatalog,
 };
-use anyhow::Result;
-use reqwest;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone)]
 pub struct User {
-    client: reqwest::Client,
+    client: Client,
     jwt_token: String,
     username: String,
 }
@@ -22,6 +22,18 @@ impl User {
         password: &str,
         platform: &Platform,
         language: &str,
+    ) -> Result<User> {
+        Client::new()
+            .login(email, password, platform, language)
+            .await
+    }
+
+    pub(crate) async fn login_with(
+        mut client: Client,
+        email: &str,
+        password: &str,
+        platform: &Platform,
+        language: &str,
     ) -> Result<User> {
         let post_body = request::Signin {
             auth_type: "header".into(),
@@ -29,48 +41,65 @@ impl User {
             password: password.into(),
         };
 
-        let req_client = reqwest::Client::new();
+        client.transport.platform = platform.clone();
+        client.transport.language = language.to_string();
 
-        let mut headers = reqwest::header::HeaderMap::new();
+        let transport = &client.transport;
+        let mut headers = transport.headers(None)?;
         headers.insert("Content-Type", "application/json; utf-8".parse()?);
         headers.insert("Authorization", "JWT".parse()?);
-        headers.insert(
-            "platform",
-            format!("{:?}", platform).to_lowercase().parse()?,
-        );
-        headers.insert("language", language.parse()?);
-
-        let raw_response = req_client
-            .post(format!("{}/auth/signin", BASE_URL))
+
+        transport.limiter.acquire().await;
+        let raw_response = transport
+            .client
+            .post(transport.url("/auth/signin"))
             .headers(headers)
-            .body(serde_json::to_string(&post_body)?)
+            .body(serde_json::to_string(&post_body).map_err(Error::Serialize)?)
             .send()
             .await?;
 
-        let jwt_token = match raw_response.headers().get("authorization") {
-            Some(x) => x.clone(),
-            None => anyhow::bail!("No jwt_token in headers!"),
-        };
+        let jwt_token = raw_response.headers().get("authorization").cloned();
+        let text = read_response(raw_response).await?;
+        let response: response::SigninWrapper = parse_payload(&text)?;
 
-        let text = raw_response.text().await?;
-        let response: response::ResponseWrapper<response::SigninWrapper> =
-            serde_json::from_str(&text)?;
+        let jwt_token = match jwt_token {
+            Some(x) => x,
+            None => return Err(Error::MissingToken),
+        };
 
-        return Ok(User {
-            client: req_client,
-            jwt_token: jwt_token.to_str()?.to_string(),
-            username: response.payload.user.ingame_name,
-        });
+        Ok(User {
+            client,
+            jwt_token: jwt_token
+                .to_str()
+                .map_err(|_| Error::MissingToken)?
+                .to_string(),
+            username: response.user.ingame_name,
+        })
     }
 
     pub fn _from_jwt_token(jwt_token: &str, username: &str) -> User {
+        User::from_client(Client::new(), jwt_token, username)
+    }
+
+    pub(crate) fn from_client(client: Client, jwt_token: &str, username: &str) -> User {
         User {
+            client,
             jwt_token: jwt_token.to_string(),
-            client: reqwest::Client::new(),
             username: username.to_string(),
         }
     }
 
+    /// replaces the rate limiter, clones made after this share the new limiter
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> User {
+        self.client.transport.limiter = limiter;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> User {
+        self.client.transport.retry = retry;
+        self
+    }
+
     pub fn _jwt_token(&self) -> String {
         self.jwt_token.clone()
     }
@@ -79,57 +108,237 @@ impl User {
         self.username.clone()
     }
 
+    pub fn platform(&self) -> &Platform {
+        self.client.platform()
+    }
+
+    pub fn language(&self) -> &str {
+        self.client.language()
+    }
+
+    /// a copy of this user for another platform, use this to override the platform for a single call
+    pub fn with_platform(&self, platform: Platform) -> User {
+        let mut user = self.clone();
+        user.client = self.client.with_platform(platform);
+        user
+    }
+
+    /// a copy of this user for another language, for example `de` or `zh-hans`
+    pub fn with_language(&self, language: &str) -> User {
+        let mut user = self.clone();
+        user.client = self.client.with_language(language);
+        user
+    }
+
+    /// the unauthenticated client this user sends its requests with
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
     pub async fn get_items(&self) -> Result<Vec<response::ShortItem>> {
-        Ok(
-            get_endpoint::<response::Items>(&self.client, "/items", &self.jwt_token)
-                .await?
-                .items,
-        )
+        self.client.get_items().await
     }
 
     pub async fn get_item<T: traits::ItemUrl>(&self, item: &T) -> Result<response::LongItem> {
-        get_endpoint::<response::LongItem>(
-            &self.client,
-            &format!("/items/{}", item.item_url()),
-            &self.jwt_token,
-        )
-        .await
+        self.client.get_item(item).await
     }
 
     pub async fn get_item_orders<T: traits::ItemUrl>(
         &self,
         item: &T,
     ) -> Result<Vec<response::Order>> {
-        Ok(get_endpoint::<response::Orders>(
-            &self.client,
-            &format!("/items/{}/orders", item.item_url()),
-            &self.jwt_token,
-        )
-        .await?
-        .orders)
+        self.client.get_item_orders(item).await
+    }
+
+    pub async fn get_order_book<T: traits::ItemUrl>(&self, item: &T) -> Result<OrderBook> {
+        self.client.get_order_book(item).await
     }
 
     pub async fn get_item_market_statistics<T: traits::ItemUrl>(
         &self,
         item: &T,
     ) -> Result<response::MarketStatisticsWrapper> {
-        get_endpoint(
-            &self.client,
-            &format!("/items/{}/statistics", item.item_url()),
-            &self.jwt_token,
+        self.client.get_item_market_statistics(item).await
+    }
+
+    pub async fn get_profile(&self, ingame_name: &str) -> Result<response::Profile> {
+        self.client.get_profile(ingame_name).await
+    }
+
+    pub async fn get_profile_statistics(
+        &self,
+        ingame_name: &str,
+    ) -> Result<response::ProfileStatistics> {
+        self.client.get_profile_statistics(ingame_name).await
+    }
+
+    pub async fn get_reviews(&self, ingame_name: &str) -> Result<Vec<response::Review>> {
+        self.client.get_reviews(ingame_name).await
+    }
+
+    /// leaves a review on someone else's profile, only one per user is allowed
+    pub async fn post_review(
+        &self,
+        ingame_name: &str,
+        review_type: &ReviewType,
+        text: &str,
+    ) -> Result<response::Review> {
+        let body = request::PostReview {
+            text: text.to_string(),
+            review_type: review_type.clone(),
+        };
+
+        Ok(post_endpoint::<response::ReviewWrapper, _>(
+            &self.client.transport,
+            &format!("/profile/{}/review", ingame_name),
+            Some(&self.jwt_token),
+            &body,
         )
-        .await
+        .await?
+        .review)
+    }
+
+    pub async fn get_riven_items(&self) -> Result<Vec<response::RivenItem>> {
+        self.client.get_riven_items().await
+    }
+
+    pub async fn get_riven_attributes(&self) -> Result<Vec<response::RivenAttributeDefinition>> {
+        self.client.get_riven_attributes().await
+    }
+
+    pub async fn get_riven_catalog(&self) -> Result<RivenCatalog> {
+        self.client.get_riven_catalog().await
+    }
+
+    pub async fn get_lich_weapons(&self) -> Result<Vec<response::LichWeapon>> {
+        self.client.get_lich_weapons().await
+    }
+
+    pub async fn get_lich_ephemeras(&self) -> Result<Vec<response::Ephemera>> {
+        self.client.get_lich_ephemeras().await
+    }
+
+    pub async fn get_sister_weapons(&self) -> Result<Vec<response::LichWeapon>> {
+        self.client.get_sister_weapons().await
+    }
+
+    pub async fn get_sister_ephemeras(&self) -> Result<Vec<response::Ephemera>> {
+        self.client.get_sister_ephemeras().await
+    }
+
+    pub async fn search_riven_auctions(
+        &self,
+        search: &RivenSearch,
+    ) -> Result<Vec<response::ProfileAuction>> {
+        self.client.search_riven_auctions(search).await
+    }
+
+    pub async fn search_lich_auctions(
+        &self,
+        search: &LichSearch,
+    ) -> Result<Vec<response::ProfileAuction>> {
+        self.client.search_lich_auctions(search).await
+    }
+
+    /// sets the status shown on your profile and orders, for example ingame before trading.
+    /// this opens a websocket connection just for this, use `ws::Connection::set_status` if
+    /// you already have one open.
+    #[cfg(feature = "ws")]
+    pub async fn set_status(&self, status: &crate::shared::UserStatus) -> Result<()> {
+        let connection = crate::ws::Connection::connect(self).await?;
+        connection.set_status(status)?;
+        connection.shutdown().await;
+
+        Ok(())
+    }
+
+    pub async fn get_chats(&self) -> Result<Vec<response::Chat>> {
+        Ok(get_endpoint::<response::Chats>(
+            &self.client.transport,
+            "/im/chats",
+            Some(&self.jwt_token),
+        )
+        .await?
+        .chats)
+    }
+
+    pub async fn get_chat_messages<T: ChatID>(
+        &self,
+        chat: &T,
+    ) -> Result<Vec<response::ChatMessage>> {
+        Ok(get_endpoint::<response::ChatMessages>(
+            &self.client.transport,
+            &format!("/im/chats/{}", chat.chat_id()),
+            Some(&self.jwt_token),
+        )
+        .await?
+        .messages)
+    }
+
+    /// messages can only be sent over the websocket, this opens a connection just for this and
+    /// waits until the site echoes the message back. fails with `Error::NotSent` if it doesn't
+    /// within 10 seconds.
+    #[cfg(feature = "ws")]
+    pub async fn send_message<T: ChatID>(
+        &self,
+        chat: &T,
+        message: &str,
+    ) -> Result<response::ChatMessage> {
+        use crate::ws::{Connection, Event};
+        use futures_util::StreamExt;
+
+        let mut connection = Connection::connect(self).await?;
+        connection.send_message(chat, message).await?;
+
+        let echo = async {
+            while let Some(event) = connection.next().await {
+                if let Event::ChatMessage(x) = event {
+                    if x.chat_id == chat.chat_id() && x.message == message {
+                        return Some(*x);
+                    }
+                }
+            }
+            None
+        };
+        let echo = tokio::time::timeout(std::time::Duration::from_secs(10), echo).await;
+        connection.shutdown().await;
+
+        match echo {
+            Ok(Some(x)) => Ok(x),
+            _ => Err(Error::NotSent),
+        }
+    }
+
+    #[cfg(feature = "ws")]
+    pub async fn mark_read<T: ChatID>(&self, chat: &T) -> Result<()> {
+        let connection = crate::ws::Connection::connect(self).await?;
+        let result = connection.mark_read(chat).await;
+        connection.shutdown().await;
+
+        result
     }
 
     pub async fn get_user_orders(&self) -> Result<response::ExistingProfileOrders> {
         get_endpoint(
-            &self.client,
+            &self.client.transport,
             &format!("/profile/{}/orders", &self.username),
-            &self.jwt_token,
+            Some(&self.jwt_token),
         )
         .await
     }
 
+    /// the logged-in user's recently closed orders, `last_update` is when they were closed,
+    /// parsed by `last_update_utc()` with the `chrono` feature
+    pub async fn get_closed_orders(&self) -> Result<Vec<response::ExistingProfileOrder>> {
+        Ok(get_endpoint::<response::ClosedOrders>(
+            &self.client.transport,
+            &format!("/profile/{}/orders/closed", &self.username),
+            Some(&self.jwt_token),
+        )
+        .await?
+        .closed_orders)
+    }
+
     pub async fn post_order(
         &self,
         desc: &PostOrderDescriptor,
@@ -146,9 +355,9 @@ impl User {
 
         Ok(
             post_endpoint::<ProfileOrderResponseWrapper, request::ProfileOrder>(
-                &self.client,
+                &self.client.transport,
                 "/profile/orders",
-                &self.jwt_token,
+                Some(&self.jwt_token),
                 &body,
             )
             .await?
@@ -160,12 +369,12 @@ impl User {
         &self,
         order: &T,
     ) -> Result<response::RemoveOrderResponse> {
-        Ok(delete_endpoint::<response::RemoveOrderResponse>(
-            &self.client,
+        delete_endpoint::<response::RemoveOrderResponse>(
+            &self.client.transport,
             &format!("/profile/orders/{}", order.order_id()),
-            &self.jwt_token,
+            Some(&self.jwt_token),
         )
-        .await?)
+        .await
     }
 
     pub async fn update_order<T: OrderID>(
@@ -178,26 +387,129 @@ impl User {
             platinum: desc.platinum,
             quantity: desc.quantity,
             visible: desc.visible,
-            rank: desc.rank.clone(),
+            rank: desc.rank,
             subtype: desc.subtype.clone(),
         };
 
-        Ok(put_endpoint(
-            &self.client,
+        put_endpoint(
+            &self.client.transport,
             &format!("/profile/orders/{}", order.order_id()),
-            &self.jwt_token,
+            Some(&self.jwt_token),
             &body,
         )
-        .await?)
+        .await
+    }
+
+    /// marks one of the order's items as sold, which lowers its quantity and counts towards
+    /// the item's statistics. returns the order with the new quantity, or `None` if that was the last one.
+    pub async fn close_order<T: OrderID>(
+        &self,
+        order: &T,
+    ) -> Result<Option<response::ProfileOrderResponse>> {
+        Ok(
+            put_endpoint_with_response::<response::CloseOrderResponse, _>(
+                &self.client.transport,
+                &format!("/profile/orders/close/{}", order.order_id()),
+                Some(&self.jwt_token),
+                &serde_json::json!({}),
+            )
+            .await?
+            .order,
+        )
     }
 
     pub async fn get_auctions(&self) -> Result<response::ProfileAuctions> {
-        Ok(get_endpoint(
-            &self.client,
+        get_endpoint(
+            &self.client.transport,
             &format!("/profile/{}/auctions", self.username),
-            &self.jwt_token,
+            Some(&self.jwt_token),
+        )
+        .await
+    }
+
+    pub async fn create_auction(
+        &self,
+        auction: &request::CreateAuction,
+    ) -> Result<response::ProfileAuction> {
+        Ok(post_endpoint::<response::ProfileAuctionWrapper, _>(
+            &self.client.transport,
+            "/auctions/create",
+            Some(&self.jwt_token),
+            auction,
+        )
+        .await?
+        .auction)
+    }
+
+    pub async fn update_auction<T: AuctionID>(
+        &self,
+        auction: &T,
+        update: &request::UpdateAuction,
+    ) -> Result<()> {
+        put_endpoint(
+            &self.client.transport,
+            &format!("/auctions/entry/{}", auction.auction_id()),
+            Some(&self.jwt_token),
+            update,
         )
-        .await?)
+        .await
+    }
+
+    /// turns the auction into a direct sale, bidding is disabled and `price` becomes the buyout
+    pub async fn direct_sell_auction<T: AuctionID>(&self, auction: &T, price: u32) -> Result<()> {
+        let update = request::UpdateAuction {
+            starting_price: Some(price),
+            buyout_price: Some(price),
+            is_direct_sell: Some(true),
+            ..Default::default()
+        };
+
+        self.update_auction(auction, &update).await
+    }
+
+    /// fails with `Error::BidTooLow` or `Error::AuctionClosed` if the bid wasn't accepted
+    pub async fn place_bid<T: AuctionID>(&self, auction: &T, amount: u32) -> Result<response::Bid> {
+        Ok(post_endpoint::<response::BidWrapper, _>(
+            &self.client.transport,
+            &format!("/auctions/entry/{}/bids", auction.auction_id()),
+            Some(&self.jwt_token),
+            &request::PlaceBid { value: amount },
+        )
+        .await
+        .map_err(Error::into_bid_error)?
+        .bid)
+    }
+
+    pub async fn get_auction_bids<T: AuctionID>(&self, auction: &T) -> Result<Vec<response::Bid>> {
+        Ok(get_endpoint::<response::Bids>(
+            &self.client.transport,
+            &format!("/auctions/entry/{}/bids", auction.auction_id()),
+            Some(&self.jwt_token),
+        )
+        .await?
+        .bids)
+    }
+
+    /// every bid the logged-in user placed, including on auctions that have closed since
+    pub async fn get_my_bids(&self) -> Result<Vec<response::Bid>> {
+        Ok(get_endpoint::<response::Bids>(
+            &self.client.transport,
+            "/profile/bids",
+            Some(&self.jwt_token),
+        )
+        .await?
+        .bids)
+    }
+
+    /// closes the auction without a winner, it can't be reopened
+    pub async fn close_auction<T: AuctionID>(&self, auction: &T) -> Result<()> {
+        put_endpoint(
+            &self.client.transport,
+            &format!("/auctions/entry/{}/close", auction.auction_id()),
+            Some(&self.jwt_token),
+            &serde_json::json!({}),
+        )
+        .await
     }
 }
 
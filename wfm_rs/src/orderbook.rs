@@ -0,0 +1,164 @@
+use crate::response::Order;
+use crate::shared::{OrderType, Platform, UserStatus};
+use std::cmp::Ordering;
+
+/// the orders of a single item, with helpers to find the best prices.
+/// filters return a new book, so they can be chained.
+#[derive(Clone)]
+pub struct OrderBook {
+    orders: Vec<Order>,
+}
+
+/// quantities that can be traded at a price or better
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Depth {
+    /// sell orders at or below the price
+    pub sell: u32,
+    /// buy orders at or above the price
+    pub buy: u32,
+}
+
+impl OrderBook {
+    pub fn new(orders: Vec<Order>) -> OrderBook {
+        OrderBook { orders }
+    }
+
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    pub fn into_orders(self) -> Vec<Order> {
+        self.orders
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn sell_orders(&self) -> impl Iterator<Item = &Order> {
+        self.of_type(OrderType::Sell)
+    }
+
+    pub fn buy_orders(&self) -> impl Iterator<Item = &Order> {
+        self.of_type(OrderType::Buy)
+    }
+
+    /// the cheapest visible sell order
+    pub fn best_sell(&self, online_only: bool) -> Option<&Order> {
+        self.sell_orders()
+            .filter(|x| x.visible && (!online_only || x.user.status.is_online()))
+            .min_by(|a, b| cmp_price(a, b))
+    }
+
+    /// the highest visible buy order
+    pub fn best_buy(&self, online_only: bool) -> Option<&Order> {
+        self.buy_orders()
+            .filter(|x| x.visible && (!online_only || x.user.status.is_online()))
+            .max_by(|a, b| cmp_price(a, b))
+    }
+
+    /// best sell price minus best buy price, `None` if either side is empty
+    pub fn spread(&self, online_only: bool) -> Option<f64> {
+        Some(self.best_sell(online_only)?.platinum - self.best_buy(online_only)?.platinum)
+    }
+
+    pub fn depth_at(&self, price: f64) -> Depth {
+        let mut depth = Depth::default();
+
+        for order in self.orders.iter().filter(|x| x.visible) {
+            match order.order_type {
+                OrderType::Sell if order.platinum <= price => depth.sell += order.quantity as u32,
+                OrderType::Buy if order.platinum >= price => depth.buy += order.quantity as u32,
+                _ => {}
+            }
+        }
+
+        depth
+    }
+
+    /// sell orders from cheapest to most expensive
+    pub fn sorted_sells(&self) -> Vec<&Order> {
+        let mut sells: Vec<&Order> = self.sell_orders().collect();
+        sells.sort_by(|a, b| cmp_price(a, b));
+        sells
+    }
+
+    /// buy orders from highest to lowest
+    pub fn sorted_buys(&self) -> Vec<&Order> {
+        let mut buys: Vec<&Order> = self.buy_orders().collect();
+        buys.sort_by(|a, b| cmp_price(b, a));
+        buys
+    }
+
+    pub fn filter<F: Fn(&Order) -> bool>(&self, predicate: F) -> OrderBook {
+        OrderBook {
+            orders: self
+                .orders
+                .iter()
+                .filter(|x| predicate(x))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn visible(&self) -> OrderBook {
+        self.filter(|x| x.visible)
+    }
+
+    pub fn with_status(&self, status: &UserStatus) -> OrderBook {
+        self.filter(|x| &x.user.status == status)
+    }
+
+    pub fn ingame(&self) -> OrderBook {
+        self.with_status(&UserStatus::Ingame)
+    }
+
+    /// ingame or online
+    pub fn online(&self) -> OrderBook {
+        self.filter(|x| x.user.status.is_online())
+    }
+
+    pub fn min_reputation(&self, reputation: u16) -> OrderBook {
+        self.filter(|x| x.user.reputation >= reputation)
+    }
+
+    pub fn region(&self, region: &str) -> OrderBook {
+        self.filter(|x| x.region == region)
+    }
+
+    pub fn platform(&self, platform: &Platform) -> OrderBook {
+        self.filter(|x| &x.platform == platform)
+    }
+
+    /// only orders for mods or arcanes at this rank
+    pub fn rank(&self, rank: u8) -> OrderBook {
+        self.filter(|x| x.mod_rank == Some(rank))
+    }
+
+    /// only orders for this subtype, for example `radiant` relics
+    pub fn subtype(&self, subtype: &str) -> OrderBook {
+        self.filter(|x| x.subtype.as_deref() == Some(subtype))
+    }
+
+    fn of_type(&self, order_type: OrderType) -> impl Iterator<Item = &Order> {
+        self.orders
+            .iter()
+            .filter(move |x| x.order_type == order_type)
+    }
+}
+
+impl From<Vec<Order>> for OrderBook {
+    fn from(orders: Vec<Order>) -> OrderBook {
+        OrderBook::new(orders)
+    }
+}
+
+fn cmp_price(a: &Order, b: &Order) -> Ordering {
+    a.platinum
+        .partial_cmp(&b.platinum)
+        .unwrap_or(Ordering::Equal)
+}
@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// warframe.market allows about 3 requests per second
+pub const DEFAULT_REQUESTS_PER_SECOND: f64 = 3.0;
+pub const DEFAULT_BURST: u32 = 3;
+
+/// a token bucket shared by every clone, so cloned `User`s stay under the same limit
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    bucket: Option<Arc<Mutex<Bucket>>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    /// can go negative, which means requests are queued up waiting for tokens
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// panics if `requests_per_second` isn't positive
+    pub fn new(requests_per_second: f64, burst: u32) -> RateLimiter {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        let capacity = burst.max(1) as f64;
+
+        RateLimiter {
+            bucket: Some(Arc::new(Mutex::new(Bucket {
+                capacity,
+                tokens: capacity,
+                refill_per_second: requests_per_second,
+                last_refill: Instant::now(),
+            }))),
+        }
+    }
+
+    /// never waits, use this if you're doing your own rate limiting
+    pub fn unlimited() -> RateLimiter {
+        RateLimiter { bucket: None }
+    }
+
+    /// waits until a request may be sent
+    pub async fn acquire(&self) {
+        let wait = match &self.bucket {
+            Some(bucket) => bucket.lock().unwrap().reserve(),
+            None => return,
+        };
+
+        if wait > Duration::from_secs(0) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> RateLimiter {
+        RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND, DEFAULT_BURST)
+    }
+}
+
+impl Bucket {
+    /// takes a token and returns how long to wait before it may be used
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.refill_per_second)
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::shared::OrderType;
+use crate::response::AuctionItem;
+use crate::shared::{OrderType, ReviewType};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -33,3 +34,48 @@ pub struct UpdateOrder {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<String>,
 }
+
+/// used to create an auction, see `User::create_auction`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CreateAuction {
+    pub item: AuctionItem,
+    pub note: String,
+    /// bidding starts here, leave it out for a direct sale at `buyout_price`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_price: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyout_price: Option<u32>,
+    pub minimal_reputation: u32,
+    /// private auctions are only visible through a direct link
+    pub private: bool,
+}
+
+/// only the fields that are `Some` are changed
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct UpdateAuction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starting_price: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buyout_price: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimal_reputation: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_direct_sell: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlaceBid {
+    pub value: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PostReview {
+    pub text: String,
+    pub review_type: ReviewType,
+}
@@ -1,5 +1,10 @@
-use crate::shared::{ModPolarity, OrderType, Platform};
-use crate::traits::{ItemID, ItemUrl, OrderID};
+#[cfg(feature = "chrono")]
+use crate::shared::parse_timestamp;
+use crate::shared::{
+    AttributeUnit, Element, ModPolarity, OrderType, Platform, ReviewType, RivenType, UserStatus,
+    WeaponGroup,
+};
+use crate::traits::{AuctionID, ChatID, ItemID, ItemUrl, OrderID};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -73,6 +78,11 @@ impl ItemID for ShortItem {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LongItemWrapper {
+    pub item: LongItem,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct LongItem {
     pub id: String,
@@ -153,7 +163,6 @@ pub struct Order {
     pub quantity: u16,
     pub user: OrderUser,
     pub visible: bool,
-    // TODO: parse these to timestamps
     pub last_update: String,
     pub creation_date: String,
     /// Sometimes this is a floating-point number
@@ -162,6 +171,33 @@ pub struct Order {
     pub order_type: OrderType,
     pub region: String,
     pub id: String,
+    /// only set for mods and arcanes
+    #[serde(default)]
+    pub mod_rank: Option<u8>,
+    /// only set for items with subtypes, for example `radiant` for relics or `large` for fish
+    #[serde(default)]
+    pub subtype: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl Order {
+    pub fn last_update_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.last_update)
+    }
+
+    pub fn creation_date_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.creation_date)
+    }
+
+    /// time since the order was last updated, `None` if the timestamp couldn't be parsed
+    pub fn age(&self) -> Option<chrono::Duration> {
+        Some(chrono::Utc::now().signed_duration_since(self.last_update_utc()?))
+    }
+
+    /// true if the order hasn't been updated in `max_age`, these sellers are often not around anymore
+    pub fn is_stale(&self, max_age: chrono::Duration) -> bool {
+        matches!(self.age(), Some(age) if age > max_age)
+    }
 }
 
 impl OrderID for Order {
@@ -175,9 +211,30 @@ pub struct OrderUser {
     pub reputation: u16,
     pub region: String,
     pub ingame_name: String,
-    pub status: String,
+    pub status: UserStatus,
     pub id: String,
-    // missing: avatar, last_seen
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub last_seen: Option<String>,
+    /// the language the user picked on the site, for example `en`
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// the user trades with other platforms
+    #[serde(default)]
+    pub crossplay: Option<bool>,
+}
+
+#[cfg(feature = "chrono")]
+impl OrderUser {
+    /// time since the user was last online, `None` if the API didn't send it
+    pub fn since_last_seen(&self) -> Option<chrono::Duration> {
+        Some(chrono::Utc::now().signed_duration_since(self.last_seen_utc()?))
+    }
+
+    pub fn last_seen_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(self.last_seen.as_deref()?)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -208,6 +265,18 @@ pub struct MarketSubStatisticsClosed {
     pub donch_top: f32,
     pub donch_bot: f32,
     pub id: String,
+    /// only set for mods and arcanes, see `statistics::Variant`
+    #[serde(default)]
+    pub mod_rank: Option<u8>,
+    #[serde(default)]
+    pub subtype: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl MarketSubStatisticsClosed {
+    pub fn datetime_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.datetime)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -222,6 +291,61 @@ pub struct MarketSubStatisticsLive {
     pub order_type: String,
     pub moving_avg: Option<f32>,
     pub id: String,
+    #[serde(default)]
+    pub mod_rank: Option<u8>,
+    #[serde(default)]
+    pub subtype: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl MarketSubStatisticsLive {
+    pub fn datetime_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.datetime)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileStatisticsWrapper {
+    pub statistics: ProfileStatistics,
+}
+
+/// a user's trading history, public for every profile
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileStatistics {
+    /// buy and sell totals per day
+    pub closed_orders: MarketStatistics<ProfileSubStatistics>,
+    #[serde(default)]
+    pub items_traded: Vec<TradedItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileSubStatistics {
+    pub datetime: String,
+    pub buy_volume: u32,
+    pub sell_volume: u32,
+    /// platinum spent on buy orders
+    pub buy_platinum: f64,
+    /// platinum earned from sell orders
+    pub sell_platinum: f64,
+}
+
+#[cfg(feature = "chrono")]
+impl ProfileSubStatistics {
+    pub fn datetime_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.datetime)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TradedItem {
+    pub item: ShortItem,
+    pub buy_volume: u32,
+    pub sell_volume: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClosedOrders {
+    pub closed_orders: Vec<ExistingProfileOrder>,
 }
 
 /// received from placing an order
@@ -243,6 +367,23 @@ pub struct ProfileOrderResponse {
     pub visible: bool,
 }
 
+#[cfg(feature = "chrono")]
+impl ProfileOrderResponse {
+    pub fn creation_date_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.creation_date)
+    }
+
+    pub fn last_update_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.last_update)
+    }
+}
+
+/// received from closing an order, `order` is `None` once the last one was sold
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CloseOrderResponse {
+    pub order: Option<ProfileOrderResponse>,
+}
+
 impl OrderID for ProfileOrderResponse {
     fn order_id(&self) -> &str {
         self.id.as_str()
@@ -313,6 +454,21 @@ pub struct ExistingProfileOrder {
     pub platform: Platform,
     pub creation_date: String,
     pub visible: bool,
+    #[serde(default)]
+    pub mod_rank: Option<u8>,
+    #[serde(default)]
+    pub subtype: Option<String>,
+}
+
+#[cfg(feature = "chrono")]
+impl ExistingProfileOrder {
+    pub fn last_update_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.last_update)
+    }
+
+    pub fn creation_date_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.creation_date)
+    }
 }
 
 impl OrderID for ExistingProfileOrder {
@@ -375,6 +531,11 @@ pub struct ProfileAuctions {
     pub auctions: Vec<ProfileAuction>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileAuctionWrapper {
+    pub auction: ProfileAuction,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ProfileAuction {
     pub item: AuctionItem,
@@ -384,7 +545,7 @@ pub struct ProfileAuction {
     pub private: bool,
     pub visible: bool,
     pub buyout_price: Option<u32>,
-    pub owner: String,
+    pub owner: AuctionOwner,
     pub platform: Platform,
     pub closed: bool,
     pub top_bid: Option<u32>,
@@ -396,22 +557,462 @@ pub struct ProfileAuction {
     pub id: String,
 }
 
+#[cfg(feature = "chrono")]
+impl ProfileAuction {
+    pub fn created_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.created)
+    }
+
+    pub fn updated_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.updated)
+    }
+}
+
+impl AuctionID for ProfileAuction {
+    fn auction_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+/// your own auctions only contain the owner's id, searches include the whole user
 #[derive(Serialize, Deserialize, Clone)]
-pub struct AuctionItem {
-    #[serde(rename = "type")]
-    pub kind: String,
+#[serde(untagged)]
+pub enum AuctionOwner {
+    Id(String),
+    User(OrderUser),
+}
+
+impl AuctionOwner {
+    pub fn id(&self) -> &str {
+        match self {
+            AuctionOwner::Id(id) => id.as_str(),
+            AuctionOwner::User(user) => user.id.as_str(),
+        }
+    }
+}
+
+/// the item being auctioned, picked by its `type`. types this version of wfm_rs doesn't
+/// know about are kept as `Unknown` instead of failing the whole response.
+#[derive(Clone)]
+pub enum AuctionItem {
+    Riven(RivenAuctionItem),
+    KuvaLich(LichAuctionItem),
+    SisterOfParvos(LichAuctionItem),
+    Unknown {
+        kind: String,
+        data: serde_json::Value,
+    },
+}
+
+/// mirrors the known variants of `AuctionItem`, so serde handles the `type` tag
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum TaggedAuctionItem<R, L> {
+    #[serde(rename = "riven")]
+    Riven(R),
+    #[serde(rename = "lich")]
+    KuvaLich(L),
+    #[serde(rename = "sister")]
+    SisterOfParvos(L),
+}
+
+impl AuctionItem {
+    /// the `type` the API uses for this item
+    pub fn kind(&self) -> &str {
+        match self {
+            AuctionItem::Riven(_) => "riven",
+            AuctionItem::KuvaLich(_) => "lich",
+            AuctionItem::SisterOfParvos(_) => "sister",
+            AuctionItem::Unknown { kind, .. } => kind.as_str(),
+        }
+    }
+
+    pub fn weapon_url_name(&self) -> Option<&str> {
+        match self {
+            AuctionItem::Riven(x) => Some(x.weapon_url_name.as_str()),
+            AuctionItem::KuvaLich(x) | AuctionItem::SisterOfParvos(x) => {
+                Some(x.weapon_url_name.as_str())
+            }
+            AuctionItem::Unknown { data, .. } => data.get("weapon_url_name")?.as_str(),
+        }
+    }
+
+    pub fn as_riven(&self) -> Option<&RivenAuctionItem> {
+        match self {
+            AuctionItem::Riven(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// the weapon of a kuva lich or sister of parvos auction
+    pub fn as_lich(&self) -> Option<&LichAuctionItem> {
+        match self {
+            AuctionItem::KuvaLich(x) | AuctionItem::SisterOfParvos(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AuctionItem {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tagged: TaggedAuctionItem<&RivenAuctionItem, &LichAuctionItem> = match self {
+            AuctionItem::Riven(x) => TaggedAuctionItem::Riven(x),
+            AuctionItem::KuvaLich(x) => TaggedAuctionItem::KuvaLich(x),
+            AuctionItem::SisterOfParvos(x) => TaggedAuctionItem::SisterOfParvos(x),
+            // `data` still has its `type` field
+            AuctionItem::Unknown { data, .. } => return data.serialize(serializer),
+        };
+
+        tagged.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AuctionItem {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = serde_json::Value::deserialize(deserializer)?;
+        let kind = data
+            .get("type")
+            .and_then(|x| x.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if !matches!(kind.as_str(), "riven" | "lich" | "sister") {
+            return Ok(AuctionItem::Unknown { kind, data });
+        }
+
+        let tagged: TaggedAuctionItem<RivenAuctionItem, LichAuctionItem> =
+            serde_json::from_value(data).map_err(serde::de::Error::custom)?;
+
+        Ok(match tagged {
+            TaggedAuctionItem::Riven(x) => AuctionItem::Riven(x),
+            TaggedAuctionItem::KuvaLich(x) => AuctionItem::KuvaLich(x),
+            TaggedAuctionItem::SisterOfParvos(x) => AuctionItem::SisterOfParvos(x),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RivenAuctionItem {
     pub weapon_url_name: String,
+    pub name: String,
     pub attributes: Vec<RivenAttribute>,
     pub mod_rank: u8,
-    pub name: String,
     pub re_rolls: u32,
     pub mastery_level: u8,
     pub polarity: ModPolarity,
 }
 
+/// a kuva lich or sister of parvos weapon being auctioned
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LichAuctionItem {
+    pub weapon_url_name: String,
+    pub element: Element,
+    /// bonus damage in percent
+    pub damage: u8,
+    pub having_ephemera: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quirk: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct RivenAttribute {
     pub positive: bool,
     pub value: f64,
     pub url_name: String,
 }
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RivenItems {
+    pub items: Vec<RivenItem>,
+}
+
+/// a weapon that can have a riven, `url_name` is what auctions call `weapon_url_name`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RivenItem {
+    pub id: String,
+    pub url_name: String,
+    pub item_name: String,
+    pub group: WeaponGroup,
+    pub riven_type: RivenType,
+    pub icon: String,
+    pub thumb: String,
+    /// how strong the weapon's rivens roll, from about 0.5 to 1.55. not every weapon has it.
+    #[serde(default)]
+    pub disposition: Option<f64>,
+}
+
+impl ItemUrl for RivenItem {
+    fn item_url(&self) -> &str {
+        self.url_name.as_str()
+    }
+}
+
+impl ItemID for RivenItem {
+    fn item_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RivenAttributes {
+    pub attributes: Vec<RivenAttributeDefinition>,
+}
+
+/// an attribute a riven can roll, `url_name` matches `RivenAttribute::url_name`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RivenAttributeDefinition {
+    pub id: String,
+    pub url_name: String,
+    pub group: String,
+    /// shown in the riven name when this is the first positive attribute
+    pub prefix: Option<String>,
+    /// shown in the riven name when this is the second positive attribute
+    pub suffix: Option<String>,
+    pub effect: String,
+    pub units: Option<AttributeUnit>,
+    /// a lower value is better, for example recoil
+    #[serde(default)]
+    pub positive_is_negative: bool,
+    /// can only roll as a negative attribute
+    #[serde(default)]
+    pub negative_only: bool,
+    /// not a real attribute, only used as a search filter
+    #[serde(default)]
+    pub search_only: bool,
+    /// the riven types that can roll this, `None` means all of them
+    pub exclusive_to: Option<Vec<RivenType>>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LichWeapons {
+    pub weapons: Vec<LichWeapon>,
+}
+
+/// a kuva lich or sister of parvos weapon, `url_name` is what auctions call `weapon_url_name`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LichWeapon {
+    pub id: String,
+    pub url_name: String,
+    pub item_name: String,
+    pub icon: String,
+    pub thumb: String,
+}
+
+impl ItemUrl for LichWeapon {
+    fn item_url(&self) -> &str {
+        self.url_name.as_str()
+    }
+}
+
+impl ItemID for LichWeapon {
+    fn item_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Ephemeras {
+    pub ephemeras: Vec<Ephemera>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Ephemera {
+    pub id: String,
+    pub url_name: String,
+    pub item_name: String,
+    pub icon: String,
+    pub thumb: String,
+    pub animation: Option<String>,
+    pub element: Element,
+}
+
+impl ItemUrl for Ephemera {
+    fn item_url(&self) -> &str {
+        self.url_name.as_str()
+    }
+}
+
+impl ItemID for Ephemera {
+    fn item_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BidWrapper {
+    pub bid: Bid,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bids {
+    pub bids: Vec<Bid>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bid {
+    pub id: String,
+    /// the id of the auction the bid was placed on
+    pub auction: String,
+    /// the id of the bidder
+    pub user: String,
+    pub value: u32,
+    pub created: String,
+    pub updated: String,
+}
+
+#[cfg(feature = "chrono")]
+impl Bid {
+    pub fn created_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.created)
+    }
+
+    pub fn updated_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.updated)
+    }
+}
+
+impl AuctionID for Bid {
+    fn auction_id(&self) -> &str {
+        self.auction.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileWrapper {
+    pub profile: Profile,
+}
+
+/// a user's public profile, as shown on their page on the site
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub id: String,
+    pub ingame_name: String,
+    pub avatar: Option<String>,
+    pub background: Option<String>,
+    /// html
+    pub about: String,
+    pub about_raw: String,
+    pub reputation: u16,
+    pub region: String,
+    pub platform: Platform,
+    pub status: UserStatus,
+    pub last_seen: Option<String>,
+    pub banned: bool,
+    pub ban_reason: Option<String>,
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+    /// the profile belongs to the logged-in user
+    #[serde(default)]
+    pub own_profile: bool,
+}
+
+#[cfg(feature = "chrono")]
+impl Profile {
+    pub fn last_seen_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(self.last_seen.as_deref()?)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub exposed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Reviews {
+    pub reviews: Vec<Review>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReviewWrapper {
+    pub review: Review,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Review {
+    pub id: String,
+    pub user_from: ReviewUser,
+    pub text: String,
+    pub review_type: ReviewType,
+    pub date: String,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[cfg(feature = "chrono")]
+impl Review {
+    pub fn date_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.date)
+    }
+}
+
+/// the author of a review
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReviewUser {
+    pub id: String,
+    pub ingame_name: String,
+    pub avatar: Option<String>,
+    pub reputation: u16,
+    pub region: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Chats {
+    pub chats: Vec<Chat>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Chat {
+    pub id: String,
+    /// everyone in the chat except the logged-in user
+    pub chat_with: Vec<OrderUser>,
+    pub unread_count: u32,
+    pub last_update: String,
+    pub closed: bool,
+}
+
+#[cfg(feature = "chrono")]
+impl Chat {
+    pub fn last_update_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.last_update)
+    }
+}
+
+impl ChatID for Chat {
+    fn chat_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChatMessages {
+    pub messages: Vec<ChatMessage>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub id: String,
+    pub chat_id: String,
+    pub message: String,
+    /// the user id of the sender
+    pub message_from: String,
+    pub send_date: String,
+}
+
+#[cfg(feature = "chrono")]
+impl ChatMessage {
+    pub fn send_date_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_timestamp(&self.send_date)
+    }
+}
+
+impl ChatID for ChatMessage {
+    fn chat_id(&self) -> &str {
+        self.chat_id.as_str()
+    }
+}
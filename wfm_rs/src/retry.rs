@@ -0,0 +1,69 @@
+use rand::Rng;
+use reqwest::{Method, StatusCode};
+use std::time::Duration;
+
+/// statuses that are worth trying again, anything else is returned to the caller right away
+const RETRY_STATUSES: [StatusCode; 4] = [
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// total number of attempts, including the first one
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// also the longest `Retry-After` that is waited on, a longer one is returned as an error
+    pub max_delay: Duration,
+    /// POST and PUT aren't retried unless this is set, since they might have gone through already
+    pub retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+    /// sends every request exactly once
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// exponential backoff with jitter, `attempt` is the attempt that just failed, starting at 1
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        // wait at least half the delay, so the jitter never removes the backoff entirely
+        let half = delay / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    pub(crate) fn allows(&self, method: &Method, attempt: u32) -> bool {
+        attempt < self.max_attempts
+            && (self.retry_non_idempotent || *method == Method::GET || *method == Method::DELETE)
+    }
+
+    pub(crate) fn is_retry_status(status: StatusCode) -> bool {
+        RETRY_STATUSES.contains(&status)
+    }
+
+    pub(crate) fn is_transient(error: &reqwest::Error) -> bool {
+        error.is_connect() || error.is_timeout() || error.is_request() || error.is_body()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            retry_non_idempotent: false,
+        }
+    }
+}
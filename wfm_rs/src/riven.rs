@@ -0,0 +1,101 @@
+use crate::response::{RivenAttributeDefinition, RivenItem};
+use crate::search::RivenSearch;
+use std::collections::HashMap;
+
+/// returned by `RivenCatalog::check` for a search the API would reject or never match
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InvalidSearch {
+    #[error("unknown riven weapon '{0}'")]
+    UnknownWeapon(String),
+    #[error("unknown riven attribute '{0}'")]
+    UnknownAttribute(String),
+    #[error("'{0}' can only be a negative attribute")]
+    NegativeOnly(String),
+    #[error("'{attribute}' can't roll on '{weapon}'")]
+    NotOnWeapon { attribute: String, weapon: String },
+}
+
+/// every riven weapon and attribute by `url_name`, fetch it once with
+/// `Client::get_riven_catalog` and keep it around
+#[derive(Clone)]
+pub struct RivenCatalog {
+    items: HashMap<String, RivenItem>,
+    attributes: HashMap<String, RivenAttributeDefinition>,
+}
+
+impl RivenCatalog {
+    pub fn new(items: Vec<RivenItem>, attributes: Vec<RivenAttributeDefinition>) -> RivenCatalog {
+        RivenCatalog {
+            items: items.into_iter().map(|x| (x.url_name.clone(), x)).collect(),
+            attributes: attributes
+                .into_iter()
+                .map(|x| (x.url_name.clone(), x))
+                .collect(),
+        }
+    }
+
+    pub fn item(&self, url_name: &str) -> Option<&RivenItem> {
+        self.items.get(url_name)
+    }
+
+    pub fn attribute(&self, url_name: &str) -> Option<&RivenAttributeDefinition> {
+        self.attributes.get(url_name)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &RivenItem> {
+        self.items.values()
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = &RivenAttributeDefinition> {
+        self.attributes.values()
+    }
+
+    /// the attributes a weapon's riven can roll, `None` if the weapon is unknown
+    pub fn attributes_for(&self, weapon_url_name: &str) -> Option<Vec<&RivenAttributeDefinition>> {
+        let item = self.item(weapon_url_name)?;
+
+        Some(
+            self.attributes()
+                .filter(|x| !x.search_only)
+                .filter(|x| match &x.exclusive_to {
+                    Some(types) => types.contains(&item.riven_type),
+                    None => true,
+                })
+                .collect(),
+        )
+    }
+
+    /// checks the weapon and attributes of a search before it's sent
+    pub fn check(&self, search: &RivenSearch) -> Result<(), InvalidSearch> {
+        let item = match search.weapon_url_name() {
+            Some(weapon) => Some(
+                self.item(weapon)
+                    .ok_or_else(|| InvalidSearch::UnknownWeapon(weapon.to_string()))?,
+            ),
+            None => None,
+        };
+
+        let positive = search.positive_stats().map(|x| (x, true));
+        let negative = search.negative_stats().map(|x| (x, false));
+        for (url_name, is_positive) in positive.chain(negative) {
+            let attribute = self
+                .attribute(url_name)
+                .ok_or_else(|| InvalidSearch::UnknownAttribute(url_name.to_string()))?;
+
+            if is_positive && attribute.negative_only {
+                return Err(InvalidSearch::NegativeOnly(url_name.to_string()));
+            }
+
+            if let (Some(item), Some(types)) = (item, &attribute.exclusive_to) {
+                if !types.contains(&item.riven_type) {
+                    return Err(InvalidSearch::NotOnWeapon {
+                        attribute: url_name.to_string(),
+                        weapon: item.url_name.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
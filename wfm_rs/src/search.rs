@@ -0,0 +1,238 @@
+use crate::shared::{AuctionSort, BuyoutPolicy, Element, ModPolarity, Platform};
+use serde::Serialize;
+
+/// search query for riven auctions, every filter is optional
+#[derive(Serialize, Clone, Debug)]
+pub struct RivenSearch {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weapon_url_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    positive_stats: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    negative_stats: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    polarity: Option<ModPolarity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mastery_rank_min: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mastery_rank_max: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    re_rolls_min: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    re_rolls_max: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buyout_policy: Option<BuyoutPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<AuctionSort>,
+    /// sent as a header, not in the query
+    #[serde(skip)]
+    platform: Option<Platform>,
+}
+
+impl RivenSearch {
+    pub fn new() -> RivenSearch {
+        RivenSearch {
+            kind: "riven",
+            weapon_url_name: None,
+            positive_stats: None,
+            negative_stats: None,
+            polarity: None,
+            mastery_rank_min: None,
+            mastery_rank_max: None,
+            re_rolls_min: None,
+            re_rolls_max: None,
+            buyout_policy: None,
+            sort_by: None,
+            platform: None,
+        }
+    }
+
+    pub fn weapon(mut self, weapon_url_name: &str) -> RivenSearch {
+        self.weapon_url_name = Some(weapon_url_name.to_string());
+        self
+    }
+
+    /// the riven needs this positive attribute, can be called up to three times
+    pub fn positive(mut self, attribute_url_name: &str) -> RivenSearch {
+        push_stat(&mut self.positive_stats, attribute_url_name);
+        self
+    }
+
+    pub fn negative(mut self, attribute_url_name: &str) -> RivenSearch {
+        push_stat(&mut self.negative_stats, attribute_url_name);
+        self
+    }
+
+    pub fn polarity(mut self, polarity: ModPolarity) -> RivenSearch {
+        self.polarity = Some(polarity);
+        self
+    }
+
+    pub fn mastery(mut self, min: Option<u8>, max: Option<u8>) -> RivenSearch {
+        self.mastery_rank_min = min;
+        self.mastery_rank_max = max;
+        self
+    }
+
+    pub fn re_rolls(mut self, min: Option<u32>, max: Option<u32>) -> RivenSearch {
+        self.re_rolls_min = min;
+        self.re_rolls_max = max;
+        self
+    }
+
+    pub fn buyout_policy(mut self, policy: BuyoutPolicy) -> RivenSearch {
+        self.buyout_policy = Some(policy);
+        self
+    }
+
+    pub fn sort_by(mut self, sort: AuctionSort) -> RivenSearch {
+        self.sort_by = Some(sort);
+        self
+    }
+
+    /// defaults to the platform of the client the search is sent with
+    pub fn platform(mut self, platform: Platform) -> RivenSearch {
+        self.platform = Some(platform);
+        self
+    }
+
+    pub(crate) fn platform_override(&self) -> Option<&Platform> {
+        self.platform.as_ref()
+    }
+
+    pub(crate) fn weapon_url_name(&self) -> Option<&str> {
+        self.weapon_url_name.as_deref()
+    }
+
+    pub(crate) fn positive_stats(&self) -> impl Iterator<Item = &str> {
+        split_stats(&self.positive_stats)
+    }
+
+    pub(crate) fn negative_stats(&self) -> impl Iterator<Item = &str> {
+        split_stats(&self.negative_stats)
+    }
+}
+
+impl Default for RivenSearch {
+    fn default() -> RivenSearch {
+        RivenSearch::new()
+    }
+}
+
+/// search query for kuva lich auctions, or sister of parvos auctions with `sister()`
+#[derive(Serialize, Clone, Debug)]
+pub struct LichSearch {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weapon_url_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    element: Option<Element>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ephemera: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    having_ephemera: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    damage_min: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    damage_max: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    buyout_policy: Option<BuyoutPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort_by: Option<AuctionSort>,
+    #[serde(skip)]
+    platform: Option<Platform>,
+}
+
+impl LichSearch {
+    pub fn new() -> LichSearch {
+        LichSearch {
+            kind: "lich",
+            weapon_url_name: None,
+            element: None,
+            ephemera: None,
+            having_ephemera: None,
+            damage_min: None,
+            damage_max: None,
+            buyout_policy: None,
+            sort_by: None,
+            platform: None,
+        }
+    }
+
+    /// search sister of parvos auctions instead, the filters are the same
+    pub fn sister(mut self) -> LichSearch {
+        self.kind = "sister";
+        self
+    }
+
+    pub fn weapon(mut self, weapon_url_name: &str) -> LichSearch {
+        self.weapon_url_name = Some(weapon_url_name.to_string());
+        self
+    }
+
+    pub fn element(mut self, element: Element) -> LichSearch {
+        self.element = Some(element);
+        self
+    }
+
+    pub fn ephemera(mut self, ephemera_url_name: &str) -> LichSearch {
+        self.ephemera = Some(ephemera_url_name.to_string());
+        self
+    }
+
+    pub fn having_ephemera(mut self, having_ephemera: bool) -> LichSearch {
+        self.having_ephemera = Some(having_ephemera);
+        self
+    }
+
+    /// bonus damage in percent, between 25 and 60
+    pub fn damage(mut self, min: Option<u8>, max: Option<u8>) -> LichSearch {
+        self.damage_min = min;
+        self.damage_max = max;
+        self
+    }
+
+    pub fn buyout_policy(mut self, policy: BuyoutPolicy) -> LichSearch {
+        self.buyout_policy = Some(policy);
+        self
+    }
+
+    pub fn sort_by(mut self, sort: AuctionSort) -> LichSearch {
+        self.sort_by = Some(sort);
+        self
+    }
+
+    /// defaults to the platform of the client the search is sent with
+    pub fn platform(mut self, platform: Platform) -> LichSearch {
+        self.platform = Some(platform);
+        self
+    }
+
+    pub(crate) fn platform_override(&self) -> Option<&Platform> {
+        self.platform.as_ref()
+    }
+}
+
+impl Default for LichSearch {
+    fn default() -> LichSearch {
+        LichSearch::new()
+    }
+}
+
+/// the API takes multiple attributes as a comma separated list
+fn push_stat(stats: &mut Option<String>, url_name: &str) {
+    match stats {
+        Some(x) => {
+            x.push(',');
+            x.push_str(url_name);
+        }
+        None => *stats = Some(url_name.to_string()),
+    }
+}
+
+fn split_stats(stats: &Option<String>) -> impl Iterator<Item = &str> {
+    stats.iter().flat_map(|x| x.split(','))
+}
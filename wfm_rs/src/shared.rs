@@ -1,25 +1,199 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "lowercase")]
-pub enum OrderType {
-    Buy,
-    Sell,
+/// parses one of the ISO 8601 timestamps the API sends, `None` if it isn't one
+#[cfg(feature = "chrono")]
+pub fn parse_timestamp(raw: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    raw.parse().ok()
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum Platform {
-    Pc,
-    Xbox,
-    Ps4,
+/// returned when parsing user input into one of the enums below fails
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("unknown {kind} '{value}'")]
+pub struct ParseEnumError {
+    pub kind: &'static str,
+    pub value: String,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
-#[serde(rename_all = "lowercase")]
-pub enum ModPolarity {
-    Madurai,
-    Vazarin,
-    Naramon,
-    Zenurik,
+/// an enum that (de)serializes from a lowercase string, with an `Unknown` fallback so new
+/// values from the API don't break parsing. `FromStr` only accepts the known values.
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$vmeta:meta])* $variant:ident => $value:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)+
+            /// a value this version of wfm_rs doesn't know about
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Unknown(value) => value.as_str(),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> $name {
+                match value.as_str() {
+                    $($value => $name::$variant,)+
+                    _ => $name::Unknown(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                match value {
+                    $name::Unknown(value) => value,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseEnumError;
+
+            fn from_str(s: &str) -> Result<$name, ParseEnumError> {
+                match $name::from(s.trim().to_lowercase()) {
+                    $name::Unknown(value) => Err(ParseEnumError {
+                        kind: stringify!($name),
+                        value,
+                    }),
+                    known => Ok(known),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+    };
+}
+
+string_enum! {
+    OrderType {
+        Buy => "buy",
+        Sell => "sell",
+    }
+}
+
+string_enum! {
+    #[derive(Default)]
+    Platform {
+        #[default]
+        Pc => "pc",
+        Xbox => "xbox",
+        Ps4 => "ps4",
+        Switch => "switch",
+    }
+}
+
+string_enum! {
+    ModPolarity {
+        Madurai => "madurai",
+        Vazarin => "vazarin",
+        Naramon => "naramon",
+        Zenurik => "zenurik",
+        Unairu => "unairu",
+        Penjaga => "penjaga",
+        Umbra => "umbra",
+        Aura => "aura",
+        /// used by auction searches to match every polarity
+        Any => "any",
+    }
+}
+
+string_enum! {
+    UserStatus {
+        Ingame => "ingame",
+        Online => "online",
+        Offline => "offline",
+        Invisible => "invisible",
+    }
+}
+
+impl UserStatus {
+    /// ingame or online, so they're likely to answer a whisper
+    pub fn is_online(&self) -> bool {
+        matches!(self, UserStatus::Ingame | UserStatus::Online)
+    }
+}
+
+string_enum! {
+    /// whether auctions with a buyout price, or only direct sales, are returned
+    BuyoutPolicy {
+        With => "with",
+        Direct => "direct",
+    }
+}
+
+string_enum! {
+    AuctionSort {
+        PriceAsc => "price_asc",
+        PriceDesc => "price_desc",
+        PositiveAttrAsc => "positive_attr_asc",
+        PositiveAttrDesc => "positive_attr_desc",
+    }
+}
+
+string_enum! {
+    /// the weapon category a riven item is listed under
+    WeaponGroup {
+        Primary => "primary",
+        Secondary => "secondary",
+        Melee => "melee",
+        Zaw => "zaw",
+        Kitgun => "kitgun",
+        Sentinel => "sentinel",
+        Archgun => "archgun",
+        Archmelee => "archmelee",
+    }
+}
+
+string_enum! {
+    /// the kind of riven mod a weapon takes, which decides the attributes it can roll
+    RivenType {
+        Rifle => "rifle",
+        Shotgun => "shotgun",
+        Pistol => "pistol",
+        Melee => "melee",
+        Zaw => "zaw",
+        Kitgun => "kitgun",
+    }
+}
+
+string_enum! {
+    AttributeUnit {
+        Percent => "percent",
+        Seconds => "seconds",
+        Multiply => "multiply",
+    }
+}
+
+string_enum! {
+    /// the bonus damage element of a kuva lich or sister weapon, also used by ephemeras
+    Element {
+        Impact => "impact",
+        Heat => "heat",
+        Cold => "cold",
+        Electricity => "electricity",
+        Toxin => "toxin",
+        Magnetic => "magnetic",
+        Radiation => "radiation",
+    }
+}
+
+string_enum! {
+    ReviewType {
+        Positive => "positive",
+        Negative => "negative",
+    }
 }
@@ -0,0 +1,66 @@
+use crate::response::{MarketStatistics, MarketSubStatisticsClosed, MarketSubStatisticsLive};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// the rank of a mod or arcane and the subtype of an item like a relic.
+/// the API keeps statistics for every combination separately, so rows of different
+/// variants shouldn't be averaged together.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Variant {
+    pub mod_rank: Option<u8>,
+    pub subtype: Option<String>,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.mod_rank, &self.subtype) {
+            (Some(rank), Some(subtype)) => write!(f, "rank {} {}", rank, subtype),
+            (Some(rank), None) => write!(f, "rank {}", rank),
+            (None, Some(subtype)) => write!(f, "{}", subtype),
+            (None, None) => write!(f, "any"),
+        }
+    }
+}
+
+/// a row of market statistics that belongs to one `Variant`
+pub trait StatisticsRow {
+    fn variant(&self) -> Variant;
+}
+
+impl StatisticsRow for MarketSubStatisticsClosed {
+    fn variant(&self) -> Variant {
+        Variant {
+            mod_rank: self.mod_rank,
+            subtype: self.subtype.clone(),
+        }
+    }
+}
+
+impl StatisticsRow for MarketSubStatisticsLive {
+    fn variant(&self) -> Variant {
+        Variant {
+            mod_rank: self.mod_rank,
+            subtype: self.subtype.clone(),
+        }
+    }
+}
+
+/// groups rows by variant, lowest rank first and rows without a rank or subtype before those with one
+pub fn group_by_variant<T: StatisticsRow>(rows: &[T]) -> BTreeMap<Variant, Vec<&T>> {
+    let mut groups: BTreeMap<Variant, Vec<&T>> = BTreeMap::new();
+    for row in rows {
+        groups.entry(row.variant()).or_default().push(row);
+    }
+
+    groups
+}
+
+impl<T: StatisticsRow> MarketStatistics<T> {
+    pub fn by_variant_48_hours(&self) -> BTreeMap<Variant, Vec<&T>> {
+        group_by_variant(&self._48_hours)
+    }
+
+    pub fn by_variant_90_days(&self) -> BTreeMap<Variant, Vec<&T>> {
+        group_by_variant(&self._90_days)
+    }
+}
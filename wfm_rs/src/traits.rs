@@ -61,3 +61,19 @@ impl AuctionID for &str {
         self
     }
 }
+
+pub trait ChatID {
+    fn chat_id(&self) -> &str;
+}
+
+impl ChatID for String {
+    fn chat_id(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl ChatID for &str {
+    fn chat_id(&self) -> &str {
+        self
+    }
+}
@@ -0,0 +1,445 @@
+//! live events from the warframe.market websocket, enabled with the `ws` feature
+//!
+//! ```no_run
+//! # async fn run(user: wfm_rs::User) -> wfm_rs::Result<()> {
+//! use futures_util::StreamExt;
+//! use wfm_rs::ws::{Connection, Event};
+//!
+//! let mut connection = Connection::connect(&user).await?;
+//! connection.subscribe_new_orders()?;
+//!
+//! while let Some(event) = connection.next().await {
+//!     if let Event::NewOrder(order) = event {
+//!         println!("{} for {} platinum", order.item.url_name, order.order.platinum);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::response::{ChatMessage, Order};
+use crate::shared::UserStatus;
+use crate::traits::{ChatID, ItemID, ItemUrl};
+use crate::{Error, Result, RetryPolicy, User};
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+pub const NEW_ORDER: &str = "@WS/SUBSCRIPTIONS/MOST_RECENT/NEW_ORDER";
+pub const SUBSCRIBE_NEW_ORDERS: &str = "@WS/SUBSCRIBE/MOST_RECENT";
+pub const ORDER_UPDATED: &str = "@WS/USER/ORDER_UPDATED";
+pub const SET_STATUS: &str = "@WS/USER/SET_STATUS";
+pub const NEW_MESSAGE: &str = "@WS/chats/NEW_MESSAGE";
+pub const SEND_MESSAGE: &str = "@WS/chats/SEND_MESSAGE";
+pub const MARK_READ: &str = "@WS/chats/MARK_AS_READ";
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Clone)]
+pub enum Event {
+    /// someone posted an order, needs `Connection::subscribe_new_orders`
+    NewOrder(Box<LiveOrder>),
+    /// one of the logged-in user's own orders changed
+    OrderUpdated(Box<LiveOrder>),
+    /// the logged-in user's status changed, for example from another browser tab
+    StatusChanged(UserStatus),
+    ChatMessage(Box<ChatMessage>),
+    /// the connection dropped and was re-established, subscriptions have been sent again
+    Reconnected,
+    /// a message type this version of wfm_rs doesn't know about
+    Unknown {
+        kind: String,
+        payload: serde_json::Value,
+    },
+}
+
+/// an order as sent over the websocket, which includes the item it's for
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LiveOrder {
+    #[serde(flatten)]
+    pub order: Order,
+    pub item: LiveOrderItem,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LiveOrderItem {
+    pub id: String,
+    pub url_name: String,
+}
+
+impl ItemUrl for LiveOrderItem {
+    fn item_url(&self) -> &str {
+        self.url_name.as_str()
+    }
+}
+
+impl ItemID for LiveOrderItem {
+    fn item_id(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct OutgoingMessage<'a> {
+    chat_id: &'a str,
+    message: &'a str,
+    /// lets the site match the echoed message to the one that was sent
+    temp_id: String,
+}
+
+#[derive(Deserialize)]
+struct OrderPayload {
+    order: LiveOrder,
+}
+
+impl Event {
+    fn parse(text: &str) -> Option<Event> {
+        let raw: RawMessage = serde_json::from_str(text).ok()?;
+
+        // a known type with an unexpected payload is passed on as unknown rather than dropped
+        let event = match raw.kind.as_str() {
+            NEW_ORDER => serde_json::from_value::<OrderPayload>(raw.payload.clone())
+                .ok()
+                .map(|x| Event::NewOrder(Box::new(x.order))),
+            ORDER_UPDATED => serde_json::from_value::<OrderPayload>(raw.payload.clone())
+                .ok()
+                .map(|x| Event::OrderUpdated(Box::new(x.order))),
+            SET_STATUS => serde_json::from_value(raw.payload.clone())
+                .ok()
+                .map(Event::StatusChanged),
+            NEW_MESSAGE => serde_json::from_value(raw.payload.clone())
+                .ok()
+                .map(|x| Event::ChatMessage(Box::new(x))),
+            _ => None,
+        };
+
+        Some(event.unwrap_or(Event::Unknown {
+            kind: raw.kind,
+            payload: raw.payload,
+        }))
+    }
+}
+
+enum Command {
+    /// `sticky` messages are sent again after reconnecting, only the last one of each type is kept.
+    /// `written` is told whether the message made it onto the socket.
+    Send {
+        kind: String,
+        text: String,
+        sticky: bool,
+        written: Option<oneshot::Sender<bool>>,
+    },
+    Close,
+}
+
+/// events that haven't been read yet, newer ones are dropped while this many are waiting
+const EVENT_BUFFER: usize = 256;
+
+/// a websocket connection that reconnects by itself, read events from it as a `Stream`.
+/// a connection that's only used for sending can leave them unread, they're dropped once
+/// `EVENT_BUFFER` are waiting.
+pub struct Connection {
+    commands: mpsc::UnboundedSender<Command>,
+    events: mpsc::Receiver<Event>,
+}
+
+impl Connection {
+    /// connects with the user's token and platform, reconnect delays follow its retry policy
+    pub async fn connect(user: &User) -> Result<Connection> {
+        let transport = &user.client().transport;
+        let url = format!(
+            "{}?platform={}",
+            transport.ws_url,
+            transport.platform.as_str()
+        );
+
+        Connection::connect_to(&url, &user._jwt_token(), transport.retry.clone()).await
+    }
+
+    /// the first connection attempt has to succeed, after that it reconnects forever
+    pub async fn connect_to(url: &str, jwt_token: &str, retry: RetryPolicy) -> Result<Connection> {
+        let target = Target {
+            url: url.to_string(),
+            // the websocket wants the token as a cookie, without the `JWT ` prefix
+            cookie: format!("JWT={}", jwt_token.trim_start_matches("JWT ")),
+        };
+        let socket = target.connect().await?;
+
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::channel(EVENT_BUFFER);
+        tokio::spawn(run(target, retry, socket, command_rx, event_tx));
+
+        Ok(Connection {
+            commands: command_tx,
+            events: event_rx,
+        })
+    }
+
+    /// receive an `Event::NewOrder` for every order posted on the site
+    pub fn subscribe_new_orders(&self) -> Result<()> {
+        self.send(SUBSCRIBE_NEW_ORDERS, serde_json::Value::Null, true)
+    }
+
+    /// send a raw message, `sticky` messages are sent again after reconnecting
+    pub fn send<T: Serialize>(&self, kind: &str, payload: T, sticky: bool) -> Result<()> {
+        self.queue(kind, payload, sticky, None)
+    }
+
+    /// sends a message that isn't sent again after reconnecting, and waits until it was written
+    pub async fn send_now<T: Serialize>(&self, kind: &str, payload: T) -> Result<()> {
+        let (written, result) = oneshot::channel();
+        self.queue(kind, payload, false, Some(written))?;
+
+        match result.await {
+            Ok(true) => Ok(()),
+            _ => Err(Error::NotSent),
+        }
+    }
+
+    fn queue<T: Serialize>(
+        &self,
+        kind: &str,
+        payload: T,
+        sticky: bool,
+        written: Option<oneshot::Sender<bool>>,
+    ) -> Result<()> {
+        let message = RawMessage {
+            kind: kind.to_string(),
+            payload: serde_json::to_value(payload).map_err(Error::Serialize)?,
+        };
+        let text = serde_json::to_string(&message).map_err(Error::Serialize)?;
+
+        self.commands
+            .send(Command::Send {
+                kind: kind.to_string(),
+                text,
+                sticky,
+                written,
+            })
+            .map_err(|_| Error::WebSocketClosed)
+    }
+
+    /// set the logged-in user's status, which is sent again after reconnecting.
+    /// only online, ingame and invisible can be set.
+    pub fn set_status(&self, status: &UserStatus) -> Result<()> {
+        self.send(SET_STATUS, status, true)
+    }
+
+    /// sends a chat message, your own message comes back as an `Event::ChatMessage` once the
+    /// site accepted it. fails with `Error::NotSent` if the connection is down.
+    pub async fn send_message<T: ChatID>(&self, chat: &T, message: &str) -> Result<()> {
+        let payload = OutgoingMessage {
+            chat_id: chat.chat_id(),
+            message,
+            temp_id: rand::random::<u64>().to_string(),
+        };
+
+        self.send_now(SEND_MESSAGE, payload).await
+    }
+
+    pub async fn mark_read<T: ChatID>(&self, chat: &T) -> Result<()> {
+        self.send_now(MARK_READ, chat.chat_id()).await
+    }
+
+    /// closes the connection, events that were already received can still be read
+    pub fn close(&self) {
+        let _ = self.commands.send(Command::Close);
+    }
+
+    /// closes the connection and waits until everything sent before was flushed
+    pub async fn shutdown(mut self) {
+        self.close();
+        while self.events.recv().await.is_some() {}
+    }
+}
+
+impl Stream for Connection {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+struct Target {
+    url: String,
+    cookie: String,
+}
+
+impl Target {
+    async fn connect(&self) -> Result<Socket> {
+        let mut request = self
+            .url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| Error::WebSocket(Box::new(e)))?;
+        request.headers_mut().insert("Cookie", self.cookie.parse()?);
+
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .map_err(|e| Error::WebSocket(Box::new(e)))?;
+
+        Ok(socket)
+    }
+}
+
+enum Disconnect {
+    /// the connection dropped, try again
+    Lost,
+    /// closed on purpose or nobody is listening anymore
+    Done,
+}
+
+async fn run(
+    target: Target,
+    retry: RetryPolicy,
+    socket: Socket,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    events: mpsc::Sender<Event>,
+) {
+    let mut sticky: Vec<(String, String)> = Vec::new();
+    let mut socket = Some(socket);
+    let mut attempt = 0;
+
+    loop {
+        let current = match socket.take() {
+            Some(x) => x,
+            None => {
+                attempt += 1;
+                let delay = retry.backoff(attempt);
+                let reconnect = async {
+                    tokio::time::sleep(delay).await;
+                    target.connect().await
+                };
+                tokio::pin!(reconnect);
+
+                // keep listening for commands while the server is unreachable, so closing
+                // or dropping the connection doesn't wait for the reconnect
+                let connected = loop {
+                    tokio::select! {
+                        result = &mut reconnect => break result,
+                        command = commands.recv() => match command {
+                            Some(Command::Send { kind, text, sticky: true, .. }) => {
+                                remember(&mut sticky, kind, text)
+                            }
+                            // there is no socket to send it on
+                            Some(Command::Send { written, .. }) => {
+                                if let Some(written) = written {
+                                    let _ = written.send(false);
+                                }
+                            }
+                            Some(Command::Close) | None => return,
+                        },
+                    }
+                };
+
+                match connected {
+                    Ok(mut x) => {
+                        attempt = 0;
+                        if replay(&mut x, &sticky).await.is_err() {
+                            continue;
+                        }
+                        if deliver(&events, Event::Reconnected).is_err() {
+                            return;
+                        }
+                        x
+                    }
+                    Err(_) => continue,
+                }
+            }
+        };
+
+        match forward(current, &mut sticky, &mut commands, &events).await {
+            Disconnect::Lost => {}
+            Disconnect::Done => return,
+        }
+    }
+}
+
+/// passes an event on without waiting for the reader, only fails once nobody is listening
+fn deliver(events: &mpsc::Sender<Event>, event: Event) -> std::result::Result<(), ()> {
+    match events.try_send(event) {
+        Err(mpsc::error::TrySendError::Closed(_)) => Err(()),
+        _ => Ok(()),
+    }
+}
+
+/// keeps the last sticky message of every kind, to send again after reconnecting
+fn remember(sticky: &mut Vec<(String, String)>, kind: String, text: String) {
+    sticky.retain(|(x, _)| x != &kind);
+    sticky.push((kind, text));
+}
+
+async fn replay(socket: &mut Socket, sticky: &[(String, String)]) -> Result<()> {
+    for (_, text) in sticky {
+        socket
+            .send(Message::Text(text.clone()))
+            .await
+            .map_err(|e| Error::WebSocket(Box::new(e)))?;
+    }
+
+    Ok(())
+}
+
+async fn forward(
+    socket: Socket,
+    sticky: &mut Vec<(String, String)>,
+    commands: &mut mpsc::UnboundedReceiver<Command>,
+    events: &mpsc::Sender<Event>,
+) -> Disconnect {
+    let (mut sink, mut source) = socket.split();
+
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Send { kind, text, sticky: keep, written }) => {
+                    if keep {
+                        remember(sticky, kind, text.clone());
+                    }
+                    let ok = sink.send(Message::Text(text)).await.is_ok();
+                    if let Some(written) = written {
+                        let _ = written.send(ok);
+                    }
+                    if !ok {
+                        return Disconnect::Lost;
+                    }
+                }
+                Some(Command::Close) | None => {
+                    let _ = sink.close().await;
+                    return Disconnect::Done;
+                }
+            },
+            message = source.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(event) = Event::parse(&text) {
+                        if deliver(events, event).is_err() {
+                            let _ = sink.close().await;
+                            return Disconnect::Done;
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return Disconnect::Lost,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use wfm_rs::response::ResponseWrapper;
+use wfm_rs::{ClientBuilder, RateLimiter, RetryPolicy, User};
+use wiremock::matchers::header;
+use wiremock::{MockServer, ResponseTemplate};
+
+pub const JWT: &str = "JWT test-token";
+pub const USERNAME: &str = "test_user";
+
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+}
+
+/// the `payload` of a fixture, for tests that need a response type as input
+pub fn payload<T: DeserializeOwned>(name: &str) -> T {
+    serde_json::from_str::<ResponseWrapper<T>>(&fixture(name))
+        .unwrap()
+        .payload
+}
+
+pub fn json(name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(fixture(name), "application/json")
+}
+
+pub fn authorized() -> wiremock::matchers::HeaderExactMatcher {
+    header("authorization", JWT)
+}
+
+/// no rate limiting and short retry delays, so the tests stay fast
+pub fn builder(server: &MockServer) -> ClientBuilder {
+    ClientBuilder::new()
+        .base_url(&server.uri())
+        .rate_limiter(RateLimiter::unlimited())
+        .retry_policy(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            retry_non_idempotent: false,
+        })
+}
+
+pub fn user(server: &MockServer) -> User {
+    builder(server).user_from_jwt_token(JWT, USERNAME)
+}
@@ -0,0 +1,974 @@
+mod common;
+
+use common::{authorized, builder, json, payload, user};
+use wfm_rs::model::{PostOrderDescriptor, UpdateOrderDescriptor};
+use wfm_rs::request::{CreateAuction, UpdateAuction};
+use wfm_rs::response::{
+    AuctionItem, AuctionOwner, Chats, ProfileAuctions, RivenAttribute, RivenAuctionItem,
+};
+use wfm_rs::search::{LichSearch, RivenSearch};
+use wfm_rs::shared::{
+    AuctionSort, BuyoutPolicy, Element, ModPolarity, OrderType, ReviewType, UserStatus, WeaponGroup,
+};
+use wfm_rs::traits::AuctionID;
+use wfm_rs::{Error, Platform};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn login() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/signin"))
+        .and(header("platform", "pc"))
+        .and(header("language", "en"))
+        .and(body_json(serde_json::json!({
+            "auth_type": "header",
+            "email": "user@example.com",
+            "password": "hunter2",
+        })))
+        .respond_with(json("signin.json").insert_header("authorization", "JWT fresh-token"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = builder(&server)
+        .login("user@example.com", "hunter2", &Platform::Pc, "en")
+        .await
+        .unwrap();
+
+    assert_eq!(user._jwt_token(), "JWT fresh-token");
+    assert_eq!(user.username(), "test_user");
+}
+
+#[tokio::test]
+async fn login_without_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/signin"))
+        .respond_with(json("signin.json"))
+        .mount(&server)
+        .await;
+
+    let result = builder(&server)
+        .login("user@example.com", "hunter2", &Platform::Pc, "en")
+        .await;
+
+    assert!(matches!(result, Err(Error::MissingToken)));
+}
+
+#[tokio::test]
+async fn get_items() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(json("items.json"))
+        .mount(&server)
+        .await;
+
+    let items = user(&server).get_items().await.unwrap();
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].url_name, "mirage_prime_systems");
+}
+
+#[tokio::test]
+async fn public_client_sends_no_authorization() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items/mirage_prime_systems/statistics"))
+        .respond_with(json("statistics.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = builder(&server).build();
+    client
+        .get_item_market_statistics(&"mirage_prime_systems")
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests[0].headers.contains_key(&"authorization".into()));
+}
+
+#[tokio::test]
+async fn get_item() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items/mirage_prime_systems"))
+        .respond_with(json("item.json"))
+        .mount(&server)
+        .await;
+
+    let item = user(&server)
+        .get_item(&"mirage_prime_systems")
+        .await
+        .unwrap();
+
+    assert_eq!(item.items_in_set.len(), 1);
+    assert_eq!(item.items_in_set[0].ducats, 45);
+}
+
+#[tokio::test]
+async fn get_item_orders() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items/mirage_prime_systems/orders"))
+        .respond_with(json("orders.json"))
+        .mount(&server)
+        .await;
+
+    let orders = user(&server)
+        .get_item_orders(&"mirage_prime_systems")
+        .await
+        .unwrap();
+
+    assert_eq!(orders.len(), 4);
+    assert_eq!(orders[1].platinum, 22.5);
+    assert_eq!(orders[3].platform, Platform::Switch);
+}
+
+#[tokio::test]
+async fn get_item_market_statistics() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items/mirage_prime_systems/statistics"))
+        .respond_with(json("statistics.json"))
+        .mount(&server)
+        .await;
+
+    let stats = user(&server)
+        .get_item_market_statistics(&"mirage_prime_systems")
+        .await
+        .unwrap();
+
+    assert_eq!(stats.statistics_closed._48_hours.len(), 2);
+    assert_eq!(stats.statistics_live._90_days[0].order_type, "buy");
+}
+
+#[tokio::test]
+async fn get_user_orders() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/orders"))
+        .and(authorized())
+        .respond_with(json("profile_orders.json"))
+        .mount(&server)
+        .await;
+
+    let orders = user(&server).get_user_orders().await.unwrap();
+
+    assert_eq!(orders.sell_orders.len(), 1);
+    assert_eq!(orders.buy_orders[0].item.url_name, "mirage_prime_systems");
+}
+
+#[tokio::test]
+async fn post_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "item_id": "5819a1f2b42e4e5b1fbb2ba1",
+            "order_type": "sell",
+            "platinum": 20,
+            "quantity": 1,
+            "visible": true,
+        })))
+        .respond_with(json("post_order.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let order = user(&server)
+        .post_order(&PostOrderDescriptor {
+            item_id: "5819a1f2b42e4e5b1fbb2ba1".into(),
+            price: 20,
+            kind: OrderType::Sell,
+            visible: true,
+            quantity: 1,
+            rank: None,
+            subtype: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(order.id, "607616ad6b2b4a01c0e1e201");
+}
+
+#[tokio::test]
+async fn remove_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/profile/orders/607616ad6b2b4a01c0e1e201"))
+        .and(authorized())
+        .respond_with(json("remove_order.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let removed = user(&server)
+        .remove_order(&"607616ad6b2b4a01c0e1e201")
+        .await
+        .unwrap();
+
+    assert_eq!(removed.order_id, "607616ad6b2b4a01c0e1e201");
+}
+
+#[tokio::test]
+async fn update_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/profile/orders/607616ad6b2b4a01c0e1e201"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "order_id": "607616ad6b2b4a01c0e1e201",
+            "platinum": 18,
+            "quantity": 2,
+            "visible": false,
+        })))
+        .respond_with(json("post_order.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    user(&server)
+        .update_order(
+            &"607616ad6b2b4a01c0e1e201",
+            &UpdateOrderDescriptor {
+                platinum: 18,
+                quantity: 2,
+                visible: false,
+                rank: None,
+                subtype: None,
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn close_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/profile/orders/close/607616ad6b2b4a01c0e1e201"))
+        .and(authorized())
+        .respond_with(json("post_order.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let order = user(&server)
+        .close_order(&"607616ad6b2b4a01c0e1e201")
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(order.quantity, 1);
+}
+
+#[tokio::test]
+async fn close_last_order() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/profile/orders/close/607616ad6b2b4a01c0e1e201"))
+        .respond_with(json("close_order_sold.json"))
+        .mount(&server)
+        .await;
+
+    let order = user(&server)
+        .close_order(&"607616ad6b2b4a01c0e1e201")
+        .await
+        .unwrap();
+
+    assert!(order.is_none());
+}
+
+#[tokio::test]
+async fn get_auctions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/auctions"))
+        .and(authorized())
+        .respond_with(json("auctions.json"))
+        .mount(&server)
+        .await;
+
+    let auctions = user(&server).get_auctions().await.unwrap();
+
+    let items: Vec<&AuctionItem> = auctions.auctions.iter().map(|x| &x.item).collect();
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0].as_riven().unwrap().polarity, ModPolarity::Madurai);
+    assert!(matches!(items[1], AuctionItem::KuvaLich(x) if x.element == Element::Heat));
+    assert!(matches!(items[2], AuctionItem::SisterOfParvos(x) if x.quirk.is_some()));
+    assert!(matches!(items[3], AuctionItem::Unknown { kind, .. } if kind == "necramech_part"));
+    assert_eq!(items[3].weapon_url_name(), Some("voidrig_casing"));
+}
+
+#[tokio::test]
+async fn get_riven_items() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/riven/items"))
+        .respond_with(json("riven_items.json"))
+        .mount(&server)
+        .await;
+
+    let items = user(&server).get_riven_items().await.unwrap();
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1].group, WeaponGroup::Melee);
+    assert_eq!(items[0].disposition, Some(0.5));
+    assert_eq!(items[1].disposition, None);
+}
+
+#[tokio::test]
+async fn get_riven_attributes() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/riven/attributes"))
+        .respond_with(json("riven_attributes.json"))
+        .mount(&server)
+        .await;
+
+    let attributes = user(&server).get_riven_attributes().await.unwrap();
+
+    assert_eq!(attributes.len(), 5);
+    assert!(attributes[3].negative_only);
+    assert!(attributes[4].search_only);
+}
+
+#[tokio::test]
+async fn get_lich_weapons_and_ephemeras() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/lich/weapons"))
+        .respond_with(json("lich_weapons.json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/lich/ephemeras"))
+        .respond_with(json("lich_ephemeras.json"))
+        .mount(&server)
+        .await;
+
+    let user = user(&server);
+    let weapons = user.get_lich_weapons().await.unwrap();
+    let ephemeras = user.get_lich_ephemeras().await.unwrap();
+
+    assert_eq!(weapons[1].url_name, "kuva_nukor");
+    assert_eq!(ephemeras[0].element, Element::Heat);
+}
+
+#[tokio::test]
+async fn get_sister_weapons_and_ephemeras() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/sister/weapons"))
+        .respond_with(json("sister_weapons.json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/sister/ephemeras"))
+        .respond_with(json("sister_ephemeras.json"))
+        .mount(&server)
+        .await;
+
+    let user = user(&server);
+    let weapons = user.get_sister_weapons().await.unwrap();
+    let ephemeras = user.get_sister_ephemeras().await.unwrap();
+
+    assert_eq!(weapons[0].item_name, "Tenet Arca Plasmor");
+    assert!(ephemeras[0].animation.is_none());
+}
+
+#[tokio::test]
+async fn create_auction() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auctions/create"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "item": {
+                "type": "riven",
+                "weapon_url_name": "soma",
+                "name": "visi-critatis",
+                "attributes": [
+                    { "positive": true, "value": 120.4, "url_name": "critical_damage" },
+                ],
+                "mastery_level": 12,
+                "re_rolls": 3,
+                "polarity": "madurai",
+                "mod_rank": 0,
+            },
+            "note": "pm me",
+            "starting_price": 500,
+            "buyout_price": 1500,
+            "minimal_reputation": 0,
+            "private": false,
+        })))
+        .respond_with(json("create_auction.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let auction = user(&server)
+        .create_auction(&CreateAuction {
+            item: AuctionItem::Riven(RivenAuctionItem {
+                weapon_url_name: "soma".into(),
+                name: "visi-critatis".into(),
+                attributes: vec![RivenAttribute {
+                    positive: true,
+                    value: 120.4,
+                    url_name: "critical_damage".into(),
+                }],
+                mastery_level: 12,
+                re_rolls: 3,
+                polarity: ModPolarity::Madurai,
+                mod_rank: 0,
+            }),
+            note: "pm me".into(),
+            starting_price: Some(500),
+            buyout_price: Some(1500),
+            minimal_reputation: 0,
+            private: false,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(auction.id, "6071c4fa3e3e7b03d0a4c001");
+}
+
+#[tokio::test]
+async fn update_auction() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "note": "price lowered",
+            "buyout_price": 1200,
+        })))
+        .respond_with(json("create_auction.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    user(&server)
+        .update_auction(
+            &"6071c4fa3e3e7b03d0a4c001",
+            &UpdateAuction {
+                note: Some("price lowered".into()),
+                buyout_price: Some(1200),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn direct_sell_auction() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "starting_price": 1000,
+            "buyout_price": 1000,
+            "is_direct_sell": true,
+        })))
+        .respond_with(json("create_auction.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    user(&server)
+        .direct_sell_auction(&"6071c4fa3e3e7b03d0a4c001", 1000)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn close_auction() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001/close"))
+        .and(authorized())
+        .respond_with(json("create_auction.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    user(&server)
+        .close_auction(&"6071c4fa3e3e7b03d0a4c001")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn place_bid() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001/bids"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({ "value": 700 })))
+        .respond_with(json("place_bid.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let auctions: ProfileAuctions = payload("auctions.json");
+    let bid = user(&server)
+        .place_bid(&auctions.auctions[0], 700)
+        .await
+        .unwrap();
+
+    assert_eq!(bid.value, 700);
+    assert_eq!(bid.auction, "6071c4fa3e3e7b03d0a4c001");
+}
+
+#[tokio::test]
+async fn bid_too_low() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001/bids"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "value": ["app.bid.too_low"] }
+        })))
+        .mount(&server)
+        .await;
+
+    let error = user(&server)
+        .place_bid(&"6071c4fa3e3e7b03d0a4c001", 100)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::BidTooLow { .. }));
+    assert_eq!(error.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+}
+
+#[tokio::test]
+async fn bid_on_closed_auction() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001/bids"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "app.auction.closed"
+        })))
+        .mount(&server)
+        .await;
+
+    let error = user(&server)
+        .place_bid(&"6071c4fa3e3e7b03d0a4c001", 800)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::AuctionClosed { .. }));
+}
+
+#[tokio::test]
+async fn bid_errors_only_come_from_bids() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "platinum": ["app.bid.too_low"] }
+        })))
+        .mount(&server)
+        .await;
+
+    let error = user(&server)
+        .post_order(&PostOrderDescriptor {
+            item_id: "5819a1f2b42e4e5b1fbb2ba1".into(),
+            price: 1,
+            kind: OrderType::Sell,
+            visible: true,
+            quantity: 1,
+            rank: None,
+            subtype: None,
+        })
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, Error::Api { .. }));
+}
+
+#[tokio::test]
+async fn get_auction_bids() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/auctions/entry/6071c4fa3e3e7b03d0a4c001/bids"))
+        .and(authorized())
+        .respond_with(json("bids.json"))
+        .mount(&server)
+        .await;
+
+    let bids = user(&server)
+        .get_auction_bids(&"6071c4fa3e3e7b03d0a4c001")
+        .await
+        .unwrap();
+
+    assert_eq!(bids.len(), 2);
+    assert_eq!(bids[0].value, 650);
+}
+
+#[tokio::test]
+async fn get_my_bids() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/bids"))
+        .and(authorized())
+        .respond_with(json("bids.json"))
+        .mount(&server)
+        .await;
+
+    let bids = user(&server).get_my_bids().await.unwrap();
+
+    assert_eq!(bids[1].auction_id(), "6071c4fa3e3e7b03d0a4c001");
+}
+
+#[tokio::test]
+async fn search_riven_auctions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/auctions/search"))
+        .and(query_param("type", "riven"))
+        .and(query_param("weapon_url_name", "soma"))
+        .and(query_param("positive_stats", "critical_damage,multishot"))
+        .and(query_param("negative_stats", "zoom"))
+        .and(query_param("polarity", "madurai"))
+        .and(query_param("mastery_rank_max", "14"))
+        .and(query_param("buyout_policy", "with"))
+        .and(query_param("sort_by", "price_asc"))
+        .and(header("platform", "xbox"))
+        .respond_with(json("auction_search.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let search = RivenSearch::new()
+        .weapon("soma")
+        .positive("critical_damage")
+        .positive("multishot")
+        .negative("zoom")
+        .polarity(ModPolarity::Madurai)
+        .mastery(None, Some(14))
+        .buyout_policy(BuyoutPolicy::With)
+        .sort_by(AuctionSort::PriceAsc)
+        .platform(Platform::Xbox);
+    let auctions = user(&server).search_riven_auctions(&search).await.unwrap();
+
+    assert_eq!(auctions.len(), 1);
+    assert_eq!(auctions[0].item.as_riven().unwrap().re_rolls, 3);
+    assert_eq!(auctions[0].owner.id(), "5962ff0a0f3139002a4bd5aa");
+    assert!(matches!(&auctions[0].owner, AuctionOwner::User(x) if x.ingame_name == "riven_seller"));
+}
+
+#[tokio::test]
+async fn search_lich_auctions() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/auctions/search"))
+        .and(query_param("type", "lich"))
+        .and(query_param("weapon_url_name", "kuva_bramma"))
+        .and(query_param("element", "radiation"))
+        .and(query_param("damage_min", "55"))
+        .and(query_param("having_ephemera", "true"))
+        .respond_with(json("lich_search.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let search = LichSearch::new()
+        .weapon("kuva_bramma")
+        .element(Element::Radiation)
+        .damage(Some(55), None)
+        .having_ephemera(true);
+    let auctions = user(&server).search_lich_auctions(&search).await.unwrap();
+
+    let item = &auctions[0].item;
+    assert_eq!(item.kind(), "lich");
+    assert!(item.as_riven().is_none());
+
+    let lich = item.as_lich().unwrap();
+    assert_eq!(lich.element, Element::Radiation);
+    assert_eq!(lich.damage, 60);
+    assert!(lich.having_ephemera);
+    assert_eq!(lich.quirk.as_deref(), Some("fear_of_heights"));
+}
+
+#[tokio::test]
+async fn get_profile() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/riven_seller"))
+        .respond_with(json("profile.json"))
+        .mount(&server)
+        .await;
+
+    let profile = user(&server).get_profile("riven_seller").await.unwrap();
+
+    assert_eq!(profile.reputation, 42);
+    assert_eq!(profile.status, UserStatus::Offline);
+    assert_eq!(profile.achievements[0].name, "Beta Tester");
+}
+
+#[tokio::test]
+async fn get_profile_statistics() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/statistics"))
+        .respond_with(json("profile_statistics.json"))
+        .mount(&server)
+        .await;
+
+    let stats = user(&server)
+        .get_profile_statistics("test_user")
+        .await
+        .unwrap();
+
+    let days = &stats.closed_orders._90_days;
+    assert_eq!(days.len(), 3);
+    assert_eq!(days[2].sell_platinum, 60.0);
+    assert_eq!(stats.items_traded[0].sell_volume, 9);
+}
+
+#[tokio::test]
+async fn get_closed_orders() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/orders/closed"))
+        .and(authorized())
+        .respond_with(json("closed_orders.json"))
+        .mount(&server)
+        .await;
+
+    let orders = user(&server).get_closed_orders().await.unwrap();
+
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].item.url_name, "mirage_prime_systems");
+}
+
+#[tokio::test]
+async fn get_reviews() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/riven_seller/reviews"))
+        .respond_with(json("reviews.json"))
+        .mount(&server)
+        .await;
+
+    let reviews = user(&server).get_reviews("riven_seller").await.unwrap();
+
+    assert_eq!(reviews.len(), 2);
+    assert_eq!(reviews[1].review_type, ReviewType::Negative);
+    assert_eq!(reviews[0].user_from.ingame_name, "happy_buyer");
+}
+
+#[tokio::test]
+async fn post_review() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/riven_seller/review"))
+        .and(authorized())
+        .and(body_json(serde_json::json!({
+            "text": "smooth trade",
+            "review_type": "positive",
+        })))
+        .respond_with(json("post_review.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let review = user(&server)
+        .post_review("riven_seller", &ReviewType::Positive, "smooth trade")
+        .await
+        .unwrap();
+
+    assert_eq!(review.text, "smooth trade");
+}
+
+#[tokio::test]
+async fn get_chats() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/im/chats"))
+        .and(authorized())
+        .respond_with(json("chats.json"))
+        .mount(&server)
+        .await;
+
+    let chats = user(&server).get_chats().await.unwrap();
+
+    assert_eq!(chats[0].unread_count, 2);
+    assert_eq!(chats[0].chat_with[0].ingame_name, "riven_seller");
+}
+
+#[tokio::test]
+async fn get_chat_messages() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/im/chats/607a1f2b42e4e5b1fbb3d001"))
+        .and(authorized())
+        .respond_with(json("chat_messages.json"))
+        .mount(&server)
+        .await;
+
+    let chats: Chats = payload("chats.json");
+    let messages = user(&server)
+        .get_chat_messages(&chats.chats[0])
+        .await
+        .unwrap();
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].message, "yes, inv me");
+}
+
+#[tokio::test]
+async fn default_headers_are_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .and(header("user-agent", "wfm_rs-tests"))
+        .respond_with(json("items.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    builder(&server)
+        .default_header(
+            reqwest::header::USER_AGENT,
+            reqwest::header::HeaderValue::from_static("wfm_rs-tests"),
+        )
+        .user_from_jwt_token(common::JWT, common::USERNAME)
+        .get_items()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn validation_errors_are_decoded() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "item_id": ["app.post_order.item_id_invalid"] }
+        })))
+        .mount(&server)
+        .await;
+
+    let error = user(&server)
+        .post_order(&PostOrderDescriptor {
+            item_id: "not_an_item".into(),
+            price: 20,
+            kind: OrderType::Sell,
+            visible: true,
+            quantity: 1,
+            rank: None,
+            subtype: None,
+        })
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(error.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+    assert_eq!(
+        error.api_error().unwrap().field("item_id").unwrap(),
+        ["app.post_order.item_id_invalid"]
+    );
+}
+
+#[tokio::test]
+async fn status_errors_without_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items/not_an_item"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/orders"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let user = user(&server);
+
+    assert!(matches!(
+        user.get_item(&"not_an_item").await,
+        Err(Error::NotFound { error: None })
+    ));
+    assert!(matches!(
+        user.get_user_orders().await,
+        Err(Error::Unauthorized { .. })
+    ));
+}
+
+#[tokio::test]
+async fn status_errors_keep_the_error_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/orders"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "error": "app.account.unauthorized"
+        })))
+        .mount(&server)
+        .await;
+
+    let error = user(&server).get_user_orders().await.err().unwrap();
+
+    assert!(matches!(error, Error::Unauthorized { error: Some(_), .. }));
+    assert_eq!(
+        error.api_error().unwrap().messages(),
+        ["app.account.unauthorized"]
+    );
+}
+
+#[tokio::test]
+async fn malformed_body_keeps_raw_response() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"payload\": {}}"))
+        .mount(&server)
+        .await;
+
+    match user(&server).get_items().await {
+        Err(Error::Deserialize { body, .. }) => assert_eq!(body, "{\"payload\": {}}"),
+        _ => panic!("expected a deserialize error"),
+    }
+}
+
+#[tokio::test]
+async fn platform_and_language_are_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile/test_user/orders"))
+        .and(header("platform", "pc"))
+        .and(header("language", "en"))
+        .respond_with(json("profile_orders.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/items/mirage_prime_systems/orders"))
+        .and(header("platform", "xbox"))
+        .and(header("language", "de"))
+        .respond_with(json("orders.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = user(&server);
+    user.get_user_orders().await.unwrap();
+    user.with_platform(Platform::Xbox)
+        .with_language("de")
+        .get_item_orders(&"mirage_prime_systems")
+        .await
+        .unwrap();
+}
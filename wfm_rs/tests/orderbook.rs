@@ -0,0 +1,69 @@
+mod common;
+
+use common::payload;
+use wfm_rs::response::Orders;
+use wfm_rs::shared::{Platform, UserStatus};
+use wfm_rs::OrderBook;
+
+fn book() -> OrderBook {
+    book_from("orders.json")
+}
+
+fn book_from(fixture: &str) -> OrderBook {
+    OrderBook::new(payload::<Orders>(fixture).orders)
+}
+
+#[test]
+fn best_prices() {
+    let book = book();
+
+    assert_eq!(book.best_sell(false).unwrap().platinum, 22.5);
+    assert_eq!(book.best_sell(true).unwrap().platinum, 25.0);
+    assert_eq!(book.best_buy(false).unwrap().platinum, 15.0);
+    assert_eq!(book.spread(true), Some(10.0));
+}
+
+#[test]
+fn depth() {
+    let depth = book().depth_at(25.0);
+
+    assert_eq!(depth.sell, 2);
+    assert_eq!(depth.buy, 0);
+    assert_eq!(book().depth_at(15.0).buy, 1);
+}
+
+#[test]
+fn filters_chain() {
+    let book = book();
+
+    assert_eq!(book.ingame().len(), 2);
+    assert_eq!(book.ingame().platform(&Platform::Pc).len(), 1);
+    assert_eq!(book.online().len(), 3);
+    assert_eq!(book.with_status(&UserStatus::Offline).len(), 1);
+    assert_eq!(book.min_reputation(13).len(), 0);
+    assert_eq!(book.region("en").len(), 4);
+    assert!(book.rank(0).is_empty());
+}
+
+#[test]
+fn sorted_views() {
+    let book = book();
+    let sells: Vec<f64> = book.sorted_sells().iter().map(|x| x.platinum).collect();
+
+    assert_eq!(sells, vec![22.5, 25.0, 30.0]);
+    assert_eq!(book.sorted_buys().len(), 1);
+}
+
+#[test]
+fn subtypes_are_kept_apart() {
+    let book = book_from("relic_orders.json");
+
+    assert_eq!(book.subtype("radiant").len(), 2);
+    assert_eq!(
+        book.subtype("radiant").best_sell(true).unwrap().platinum,
+        10.0
+    );
+    assert_eq!(book.subtype("intact").best_buy(true).unwrap().platinum, 2.0);
+    assert!(book.subtype("exceptional").is_empty());
+    assert!(book.subtype("intact").rank(0).is_empty());
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+use tokio::time::Instant;
+use wfm_rs::RateLimiter;
+
+#[tokio::test(start_paused = true)]
+async fn burst_is_spaced_out() {
+    let limiter = RateLimiter::new(3.0, 3);
+    let start = Instant::now();
+
+    // the first 3 go through at once, the other 6 have to wait for refills
+    for _ in 0..9 {
+        limiter.acquire().await;
+    }
+
+    assert_eq!(start.elapsed().as_millis(), 2000);
+}
+
+#[tokio::test(start_paused = true)]
+async fn clones_share_the_bucket() {
+    let limiter = RateLimiter::new(2.0, 1);
+    let clone = limiter.clone();
+    let start = Instant::now();
+
+    limiter.acquire().await;
+    clone.acquire().await;
+
+    assert_eq!(start.elapsed().as_millis(), 500);
+}
+
+#[tokio::test(start_paused = true)]
+async fn bucket_refills_while_idle() {
+    let limiter = RateLimiter::new(3.0, 3);
+    for _ in 0..3 {
+        limiter.acquire().await;
+    }
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let start = Instant::now();
+    for _ in 0..3 {
+        limiter.acquire().await;
+    }
+
+    assert_eq!(start.elapsed().as_millis(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unlimited_never_waits() {
+    let limiter = RateLimiter::unlimited();
+    let start = Instant::now();
+
+    for _ in 0..100 {
+        limiter.acquire().await;
+    }
+
+    assert_eq!(start.elapsed().as_millis(), 0);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::{json, user};
+use std::time::Duration;
+use wfm_rs::model::PostOrderDescriptor;
+use wfm_rs::shared::OrderType;
+use wfm_rs::{Error, RetryPolicy};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 5,
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(1000),
+        retry_non_idempotent: false,
+    }
+}
+
+#[test]
+fn backoff_grows_exponentially_with_jitter() {
+    let policy = policy();
+
+    for (attempt, full) in [(1, 100), (2, 200), (3, 400), (4, 800)].iter() {
+        for _ in 0..50 {
+            let delay = policy.backoff(*attempt).as_millis() as u64;
+            assert!(
+                delay >= full / 2 && delay <= *full,
+                "{} not in range",
+                delay
+            );
+        }
+    }
+}
+
+#[test]
+fn backoff_is_capped() {
+    let policy = policy();
+
+    for _ in 0..50 {
+        assert!(policy.backoff(30) <= Duration::from_millis(1000));
+        assert!(policy.backoff(30) >= Duration::from_millis(500));
+    }
+}
+
+#[tokio::test]
+async fn get_is_retried_after_server_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(json("items.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let items = user(&server).get_items().await.unwrap();
+
+    assert_eq!(items.len(), 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let result = user(&server).get_items().await;
+
+    assert!(matches!(result, Err(Error::RateLimited { .. })));
+}
+
+#[tokio::test]
+async fn long_retry_after_is_not_waited_on() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/items"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "3600"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = user(&server).get_items().await;
+
+    match result {
+        Err(Error::RateLimited { retry_after }) => {
+            assert_eq!(retry_after, Some(Duration::from_secs(3600)))
+        }
+        _ => panic!("expected a rate limit error"),
+    }
+}
+
+#[tokio::test]
+async fn post_is_not_retried_by_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = user(&server)
+        .post_order(&PostOrderDescriptor {
+            item_id: "5819a1f2b42e4e5b1fbb2ba1".into(),
+            price: 20,
+            kind: OrderType::Sell,
+            visible: true,
+            quantity: 1,
+            rank: None,
+            subtype: None,
+        })
+        .await;
+
+    assert!(matches!(result, Err(Error::ServerError { .. })));
+}
+
+#[tokio::test]
+async fn post_is_retried_when_enabled() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/profile/orders"))
+        .respond_with(json("post_order.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let user = user(&server).with_retry_policy(RetryPolicy {
+        retry_non_idempotent: true,
+        ..policy()
+    });
+    let order = user
+        .post_order(&PostOrderDescriptor {
+            item_id: "5819a1f2b42e4e5b1fbb2ba1".into(),
+            price: 20,
+            kind: OrderType::Sell,
+            visible: true,
+            quantity: 1,
+            rank: None,
+            subtype: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(order.id, "607616ad6b2b4a01c0e1e201");
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::payload;
+use wfm_rs::response::{RivenAttributes, RivenItems};
+use wfm_rs::riven::InvalidSearch;
+use wfm_rs::search::RivenSearch;
+use wfm_rs::shared::{AttributeUnit, RivenType};
+use wfm_rs::RivenCatalog;
+
+fn catalog() -> RivenCatalog {
+    let items: RivenItems = payload("riven_items.json");
+    let attributes: RivenAttributes = payload("riven_attributes.json");
+    RivenCatalog::new(items.items, attributes.attributes)
+}
+
+#[test]
+fn lookup() {
+    let catalog = catalog();
+
+    assert_eq!(catalog.item("soma").unwrap().riven_type, RivenType::Rifle);
+    assert_eq!(
+        catalog.attribute("range").unwrap().units,
+        Some(AttributeUnit::Seconds)
+    );
+    assert!(catalog.item("not_a_weapon").is_none());
+}
+
+#[test]
+fn attributes_for_weapon() {
+    let catalog = catalog();
+
+    let mut names: Vec<&str> = catalog
+        .attributes_for("nikana")
+        .unwrap()
+        .into_iter()
+        .map(|x| x.url_name.as_str())
+        .collect();
+    names.sort_unstable();
+
+    assert_eq!(names, ["critical_damage", "range"]);
+}
+
+#[test]
+fn valid_search() {
+    let search = RivenSearch::new()
+        .weapon("soma")
+        .positive("critical_damage")
+        .negative("recoil");
+
+    assert_eq!(catalog().check(&search), Ok(()));
+}
+
+#[test]
+fn invalid_searches() {
+    let catalog = catalog();
+
+    assert_eq!(
+        catalog.check(&RivenSearch::new().weapon("somaa")),
+        Err(InvalidSearch::UnknownWeapon("somaa".into()))
+    );
+    assert_eq!(
+        catalog.check(&RivenSearch::new().positive("crit_damage")),
+        Err(InvalidSearch::UnknownAttribute("crit_damage".into()))
+    );
+    assert_eq!(
+        catalog.check(&RivenSearch::new().positive("recoil")),
+        Err(InvalidSearch::NegativeOnly("recoil".into()))
+    );
+    assert_eq!(
+        catalog.check(&RivenSearch::new().weapon("nikana").negative("zoom")),
+        Err(InvalidSearch::NotOnWeapon {
+            attribute: "zoom".into(),
+            weapon: "nikana".into(),
+        })
+    );
+}
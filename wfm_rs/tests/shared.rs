@@ -0,0 +1,90 @@
+use wfm_rs::response::AuctionItem;
+use wfm_rs::shared::{ModPolarity, OrderType, Platform};
+
+#[test]
+fn platform_parses_known_values() {
+    assert_eq!("switch".parse::<Platform>().unwrap(), Platform::Switch);
+    assert_eq!(" PS4 ".parse::<Platform>().unwrap(), Platform::Ps4);
+    assert_eq!(Platform::Xbox.to_string(), "xbox");
+}
+
+#[test]
+fn platform_from_str_rejects_unknown_values() {
+    let error = "gamecube".parse::<Platform>().unwrap_err();
+
+    assert_eq!(error.kind, "Platform");
+    assert_eq!(error.value, "gamecube");
+}
+
+#[test]
+fn unknown_values_deserialize_into_fallback() {
+    let platform: Platform = serde_json::from_str("\"stadia\"").unwrap();
+    let order_type: OrderType = serde_json::from_str("\"trade\"").unwrap();
+    let polarity: ModPolarity = serde_json::from_str("\"koneksi\"").unwrap();
+
+    assert_eq!(platform, Platform::Unknown("stadia".into()));
+    assert_eq!(order_type, OrderType::Unknown("trade".into()));
+    assert_eq!(polarity, ModPolarity::Unknown("koneksi".into()));
+    assert_eq!(serde_json::to_string(&platform).unwrap(), "\"stadia\"");
+}
+
+#[test]
+fn known_values_round_trip() {
+    for platform in [
+        Platform::Pc,
+        Platform::Xbox,
+        Platform::Ps4,
+        Platform::Switch,
+    ]
+    .iter()
+    {
+        let json = serde_json::to_string(platform).unwrap();
+        assert_eq!(&serde_json::from_str::<Platform>(&json).unwrap(), platform);
+    }
+
+    assert_eq!(serde_json::to_string(&OrderType::Sell).unwrap(), "\"sell\"");
+}
+
+#[test]
+fn every_polarity_round_trips() {
+    let polarities = [
+        (ModPolarity::Madurai, "\"madurai\""),
+        (ModPolarity::Vazarin, "\"vazarin\""),
+        (ModPolarity::Naramon, "\"naramon\""),
+        (ModPolarity::Zenurik, "\"zenurik\""),
+        (ModPolarity::Unairu, "\"unairu\""),
+        (ModPolarity::Penjaga, "\"penjaga\""),
+        (ModPolarity::Umbra, "\"umbra\""),
+        (ModPolarity::Aura, "\"aura\""),
+        (ModPolarity::Any, "\"any\""),
+        (ModPolarity::Unknown("koneksi".into()), "\"koneksi\""),
+    ];
+
+    for (polarity, json) in polarities.iter() {
+        assert_eq!(&serde_json::to_string(polarity).unwrap(), json);
+        assert_eq!(
+            &serde_json::from_str::<ModPolarity>(json).unwrap(),
+            polarity
+        );
+    }
+}
+
+#[test]
+fn polarity_never_breaks_auction_parsing() {
+    let item: AuctionItem = serde_json::from_value(serde_json::json!({
+        "type": "riven",
+        "weapon_url_name": "braton",
+        "name": "crita-visitis",
+        "attributes": [],
+        "mod_rank": 8,
+        "re_rolls": 0,
+        "mastery_level": 8,
+        "polarity": "some_future_polarity",
+    }))
+    .unwrap();
+
+    assert_eq!(
+        item.as_riven().unwrap().polarity,
+        ModPolarity::Unknown("some_future_polarity".into())
+    );
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::payload;
+use wfm_rs::response::MarketStatisticsWrapper;
+use wfm_rs::statistics::Variant;
+
+#[test]
+fn rows_are_grouped_by_rank() {
+    let stats: MarketStatisticsWrapper = payload("mod_statistics.json");
+    let groups = stats.statistics_closed.by_variant_48_hours();
+
+    let variants: Vec<&Variant> = groups.keys().collect();
+    assert_eq!(
+        variants,
+        [
+            &Variant {
+                mod_rank: Some(0),
+                subtype: None
+            },
+            &Variant {
+                mod_rank: Some(10),
+                subtype: None
+            },
+        ]
+    );
+
+    let maxed = &groups[variants[1]];
+    assert_eq!(maxed.len(), 2);
+    assert!(maxed.iter().all(|x| x.avg_price > 90.0));
+}
+
+#[test]
+fn items_without_variants_form_one_group() {
+    let stats: MarketStatisticsWrapper = payload("statistics.json");
+    let groups = stats.statistics_closed.by_variant_48_hours();
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[&Variant::default()].len(), 2);
+}
+
+#[test]
+fn variants_display() {
+    let relic = Variant {
+        mod_rank: None,
+        subtype: Some("radiant".into()),
+    };
+    let arcane = Variant {
+        mod_rank: Some(5),
+        subtype: None,
+    };
+
+    assert_eq!(relic.to_string(), "radiant");
+    assert_eq!(arcane.to_string(), "rank 5");
+    assert_eq!(Variant::default().to_string(), "any");
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "chrono")]
+
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use common::payload;
+use wfm_rs::response::{ClosedOrders, MarketStatisticsWrapper, Order, Orders};
+
+fn orders() -> Vec<Order> {
+    payload::<Orders>("orders.json").orders
+}
+
+#[test]
+fn order_timestamps_are_parsed() {
+    let order = &orders()[0];
+
+    assert_eq!(
+        order.last_update_utc(),
+        Utc.with_ymd_and_hms(2021, 4, 14, 11, 37, 44).single()
+    );
+    assert_eq!(
+        order.creation_date_utc(),
+        Utc.with_ymd_and_hms(2021, 4, 10, 12, 0, 0).single()
+    );
+}
+
+#[test]
+fn old_orders_are_stale() {
+    let order = &orders()[0];
+
+    assert!(order.age().unwrap() > Duration::days(365));
+    assert!(order.is_stale(Duration::days(7)));
+}
+
+#[test]
+fn last_seen_is_parsed() {
+    let orders = orders();
+    let user = &orders[0].user;
+
+    assert_eq!(
+        user.last_seen_utc(),
+        Utc.with_ymd_and_hms(2021, 4, 14, 11, 40, 0).single()
+    );
+    assert!(user.since_last_seen().unwrap() > Duration::days(365));
+    assert_eq!(user.crossplay, Some(true));
+    assert!(orders[1].user.since_last_seen().is_none());
+}
+
+#[test]
+fn unparseable_timestamps_are_none() {
+    let mut order = orders()[0].clone();
+    order.last_update = "yesterday".to_string();
+
+    assert_eq!(order.last_update_utc(), None);
+    assert_eq!(order.age(), None);
+    assert!(!order.is_stale(Duration::days(7)));
+}
+
+#[test]
+fn statistics_timestamps_are_parsed() {
+    let stats: MarketStatisticsWrapper = payload("statistics.json");
+
+    assert_eq!(
+        stats.statistics_closed._90_days[0].datetime_utc(),
+        Utc.with_ymd_and_hms(2021, 4, 13, 0, 0, 0).single()
+    );
+}
+
+#[test]
+fn close_date_is_parsed() {
+    let orders: ClosedOrders = payload("closed_orders.json");
+
+    assert_eq!(
+        orders.closed_orders[0].last_update_utc(),
+        Utc.with_ymd_and_hms(2021, 4, 14, 11, 37, 44).single()
+    );
+}
@@ -0,0 +1,306 @@
+#![cfg(feature = "ws")]
+
+mod common;
+
+use common::payload;
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use wfm_rs::shared::UserStatus;
+use wfm_rs::ws::{Connection, Event};
+use wfm_rs::{ClientBuilder, Error, RetryPolicy};
+
+fn retry() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        retry_non_idempotent: false,
+    }
+}
+
+fn new_order_message() -> String {
+    let orders: serde_json::Value = payload("orders.json");
+    let mut order = orders["orders"][0].clone();
+    order["item"] = serde_json::json!({
+        "id": "5819a1f2b42e4e5b1fbb2ba1",
+        "url_name": "mirage_prime_systems",
+    });
+
+    serde_json::json!({
+        "type": "@WS/SUBSCRIPTIONS/MOST_RECENT/NEW_ORDER",
+        "payload": { "order": order },
+    })
+    .to_string()
+}
+
+/// what the stub saw on each connection: the cookie header and the first text message
+struct Seen {
+    cookie: String,
+    first_message: Option<String>,
+}
+
+/// accepts connections forever, sending `messages` on every connection.
+/// if `wait_for_message` is set it waits for a message from the client before sending,
+/// and the first connection is dropped right after that to test reconnecting.
+// the handshake callback's error type is defined by tungstenite
+#[allow(clippy::result_large_err)]
+async fn stub(
+    messages: Vec<String>,
+    wait_for_message: bool,
+) -> (String, mpsc::UnboundedReceiver<Seen>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut connection = 0;
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            connection += 1;
+
+            let mut cookie = String::new();
+            let callback = |request: &Request, response: Response| {
+                cookie = request.headers()["cookie"].to_str().unwrap().to_string();
+                Ok(response)
+            };
+            let mut socket = tokio_tungstenite::accept_hdr_async(stream, callback)
+                .await
+                .unwrap();
+
+            let first_message = if wait_for_message {
+                match socket.next().await {
+                    Some(Ok(Message::Text(text))) => Some(text),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            tx.send(Seen {
+                cookie,
+                first_message,
+            })
+            .unwrap();
+
+            if wait_for_message && connection == 1 {
+                drop(socket);
+                continue;
+            }
+
+            for message in messages.iter() {
+                socket.send(Message::Text(message.clone())).await.unwrap();
+            }
+            tokio::spawn(async move { while socket.next().await.is_some() {} });
+        }
+    });
+
+    (url, rx)
+}
+
+#[tokio::test]
+async fn receives_typed_events() {
+    let messages = vec![
+        new_order_message(),
+        r#"{"type": "@WS/USER/SET_STATUS", "payload": "ingame"}"#.to_string(),
+        serde_json::json!({
+            "type": "@WS/chats/NEW_MESSAGE",
+            "payload": {
+                "id": "60776b0ac0a2a1022af5b001",
+                "chat_id": "60776b0ac0a2a1022af5b000",
+                "message": "hi, is the mirage prime systems still available?",
+                "message_from": "5962ff0a0f3139002a4bd501",
+                "send_date": "2021-04-14T11:40:00.000+00:00",
+            },
+        })
+        .to_string(),
+        r#"{"type": "@WS/MESSAGE/ONLINE_COUNT", "payload": {"total_users": 5000}}"#.to_string(),
+    ];
+    let (url, mut seen) = stub(messages, false).await;
+
+    let mut connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+
+    match connection.next().await {
+        Some(Event::NewOrder(order)) => {
+            assert_eq!(order.item.url_name, "mirage_prime_systems");
+            assert_eq!(order.order.platinum, 25.0);
+        }
+        _ => panic!("expected a new order"),
+    }
+    assert!(matches!(
+        connection.next().await,
+        Some(Event::StatusChanged(UserStatus::Ingame))
+    ));
+    match connection.next().await {
+        Some(Event::ChatMessage(message)) => {
+            assert_eq!(message.chat_id, "60776b0ac0a2a1022af5b000")
+        }
+        _ => panic!("expected a chat message"),
+    }
+    match connection.next().await {
+        Some(Event::Unknown { kind, payload }) => {
+            assert_eq!(kind, "@WS/MESSAGE/ONLINE_COUNT");
+            assert_eq!(payload["total_users"], 5000);
+        }
+        _ => panic!("expected an unknown event"),
+    }
+
+    assert_eq!(seen.recv().await.unwrap().cookie, "JWT=test-token");
+}
+
+#[tokio::test]
+async fn subscriptions_are_sent_again_after_reconnecting() {
+    let (url, mut seen) = stub(vec![new_order_message()], true).await;
+
+    let mut connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+    connection.subscribe_new_orders().unwrap();
+
+    let subscribe = r#"{"type":"@WS/SUBSCRIBE/MOST_RECENT","payload":null}"#;
+    assert_eq!(seen.recv().await.unwrap().first_message.unwrap(), subscribe);
+    assert!(matches!(connection.next().await, Some(Event::Reconnected)));
+    assert_eq!(seen.recv().await.unwrap().first_message.unwrap(), subscribe);
+    assert!(matches!(connection.next().await, Some(Event::NewOrder(_))));
+}
+
+#[tokio::test]
+async fn status_is_set_and_flushed_on_shutdown() {
+    let (url, mut seen) = stub(Vec::new(), true).await;
+
+    let connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+    connection.set_status(&UserStatus::Ingame).unwrap();
+    connection.shutdown().await;
+
+    assert_eq!(
+        seen.recv().await.unwrap().first_message.unwrap(),
+        r#"{"type":"@WS/USER/SET_STATUS","payload":"ingame"}"#
+    );
+}
+
+#[tokio::test]
+async fn chat_message_is_sent() {
+    let (url, mut seen) = stub(Vec::new(), true).await;
+
+    let connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+    connection
+        .send_message(&"607a1f2b42e4e5b1fbb3d001", "wtb soma riven")
+        .await
+        .unwrap();
+    connection.shutdown().await;
+
+    let sent: serde_json::Value =
+        serde_json::from_str(&seen.recv().await.unwrap().first_message.unwrap()).unwrap();
+    assert_eq!(sent["type"], "@WS/chats/SEND_MESSAGE");
+    assert_eq!(sent["payload"]["chat_id"], "607a1f2b42e4e5b1fbb3d001");
+    assert_eq!(sent["payload"]["message"], "wtb soma riven");
+    assert!(sent["payload"]["temp_id"].is_string());
+}
+
+#[tokio::test]
+async fn shutdown_returns_while_reconnecting() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        // dropping the socket and the listener leaves nothing to reconnect to
+        tokio_tungstenite::accept_async(stream).await.unwrap()
+    });
+
+    let connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+    drop(server.await.unwrap());
+    connection.set_status(&UserStatus::Invisible).unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), connection.shutdown())
+        .await
+        .expect("shutdown waited for the reconnect");
+}
+
+/// answers every chat message like the site does, by echoing it back as a new message
+/// when `echo` is set, and otherwise ignores it
+async fn chat_stub(echo: bool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/socket", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        while let Some(Ok(Message::Text(text))) = socket.next().await {
+            let sent: serde_json::Value = serde_json::from_str(&text).unwrap();
+            if !echo || sent["type"] != "@WS/chats/SEND_MESSAGE" {
+                continue;
+            }
+            let reply = serde_json::json!({
+                "type": "@WS/chats/NEW_MESSAGE",
+                "payload": {
+                    "id": "60776b0ac0a2a1022af5b002",
+                    "chat_id": sent["payload"]["chat_id"],
+                    "message": sent["payload"]["message"],
+                    "message_from": "5962ff0a0f3139002a4bd501",
+                    "send_date": "2021-04-14T11:41:00.000+00:00",
+                },
+            });
+            socket.send(Message::Text(reply.to_string())).await.unwrap();
+        }
+    });
+
+    url
+}
+
+#[tokio::test]
+async fn send_message_waits_for_the_echo() {
+    let url = chat_stub(true).await;
+    let user = ClientBuilder::new()
+        .ws_url(&url)
+        .user_from_jwt_token("JWT test-token", "test_user");
+
+    let message = user
+        .send_message(&"607a1f2b42e4e5b1fbb3d001", "wtb soma riven")
+        .await
+        .unwrap();
+
+    assert_eq!(message.id, "60776b0ac0a2a1022af5b002");
+}
+
+#[tokio::test(start_paused = true)]
+async fn send_message_fails_without_an_echo() {
+    let url = chat_stub(false).await;
+    let user = ClientBuilder::new()
+        .ws_url(&url)
+        .user_from_jwt_token("JWT test-token", "test_user");
+
+    let result = user
+        .send_message(&"607a1f2b42e4e5b1fbb3d001", "wtb soma riven")
+        .await;
+
+    assert!(matches!(result, Err(Error::NotSent)));
+}
+
+#[tokio::test]
+async fn unread_events_are_dropped_when_the_buffer_is_full() {
+    let messages = vec![r#"{"type": "@WS/USER/SET_STATUS", "payload": "online"}"#.to_string(); 300];
+    let (url, mut seen) = stub(messages, false).await;
+
+    let mut connection = Connection::connect_to(&url, "JWT test-token", retry())
+        .await
+        .unwrap();
+    seen.recv().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    connection.close();
+
+    let mut received = 0;
+    while connection.next().await.is_some() {
+        received += 1;
+    }
+    assert_eq!(received, 256);
+}